@@ -0,0 +1,28 @@
+use tsp_solver::dataset::{Dataset,DatasetOptions};
+use tsp_solver::dynamic::{self,NewNode};
+use tsp_solver::generation::Generation;
+
+// solve the demo dataset, then add a city and remove another one, continuing the search from the repaired tours
+fn main() {
+  let options = DatasetOptions::default();
+  let mut rng = rand::thread_rng();
+
+  let dataset = Dataset::from_file("datasets/demo/demo.json", &options);
+  let mut generation = Generation::new(1, 20, 50, 0, &dataset, &mut rng);
+  for _ in 1..10 {
+    generation = generation.evolve(&mut rng, 4, 10);
+  }
+  println!("before the updates :\n{}\n", generation.population[0]);
+
+  // the distances from and to Par, Mar, Lyo, Tou, Nic, Bez and Nar
+  let distances = vec![200.0, 620.0, 310.0, 520.0, 780.0, 240.0, 300.0];
+  let dataset = dynamic::insert_node(&dataset, "Dij", NewNode::Distances { from: distances.clone(), to: distances }, &options);
+  let dataset = dynamic::remove_node(&dataset, "Nic", &options);
+
+  let mut generation = dynamic::warm_generation(&generation, &dataset, &mut rng);
+  println!("once repaired :\n{}\n", generation.population[0]);
+  for _ in 10..20 {
+    generation = generation.evolve(&mut rng, 4, 10);
+  }
+  println!("after the updates :\n{}", generation.population[0]);
+}
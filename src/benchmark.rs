@@ -0,0 +1,84 @@
+use std::hint::black_box;
+use std::time::Instant;
+use rand::prelude::*;
+use crate::dataset::{Dataset,Matrix};
+use crate::storage::{Layout,Precision};
+use crate::utils::ThousandsDisplayPolicy;
+
+// number of distances read by each measure
+pub const BENCHMARK_LOOKUPS: usize = 20_000_000;
+
+// returns the memory used by the distances of a matrix, in bytes
+fn memory_size(matrix: &Matrix) -> usize {
+  match matrix {
+    Matrix::Distances(matrix) => matrix.memory_size(),
+    Matrix::CompactDistances(matrix) => matrix.memory_size(),
+    Matrix::IntegerDistances(matrix) => matrix.memory_size(),
+    Matrix::Locations(locations, _) => locations.len() * std::mem::size_of::<(f64, f64)>(),
+    Matrix::CachedLocations(cache) => cache.memory_size()
+  }
+}
+
+// measure the number of distances read per second, in the given order
+fn throughput<F: Fn(usize, usize) -> f64>(get: F, pairs: &[(u32, u32)]) -> f64 {
+  let stopwatch = Instant::now();
+  let mut total = 0.0;
+  for &(node1, node2) in pairs.iter() {
+    total += get(node1 as usize, node2 as usize);
+  }
+  black_box(total);
+  pairs.len() as f64 / stopwatch.elapsed().as_secs_f64()
+}
+
+// display the memory used and the throughput of a storage
+fn display_measure<F: Fn(usize, usize) -> f64>(name: &str, memory_size: usize, get: F, random_pairs: &[(u32, u32)], tour_pairs: &[(u32, u32)]) {
+  println!(
+    "{:<20} · {:>15} bytes · {:>15} random lookups/s · {:>15} tour lookups/s",
+    name,
+    memory_size.thousands(),
+    (throughput(&get, random_pairs) as u64).thousands(),
+    (throughput(&get, tour_pairs) as u64).thousands()
+  );
+}
+
+// compare the memory used and the throughput of Matrix::get for every storage of the dataset distances
+// distances are read in a random order, and along random tours (like when computing lengths)
+pub fn storage_throughput(dataset: &Dataset, rng: &mut ThreadRng) {
+  let size = dataset.size;
+
+  // generate the pairs of nodes to read
+  let random_pairs: Vec<(u32, u32)> = (0..BENCHMARK_LOOKUPS)
+    .map(|_| (rng.gen_range(0..size) as u32, rng.gen_range(0..size) as u32))
+    .collect();
+  let mut tour: Vec<u32> = (0..size as u32).collect();
+  let mut tour_pairs: Vec<(u32, u32)> = Vec::with_capacity(BENCHMARK_LOOKUPS);
+  while tour_pairs.len() < BENCHMARK_LOOKUPS {
+    tour.shuffle(rng);
+    tour_pairs.extend(tour.windows(2).map(|edge| (edge[0], edge[1])).take(BENCHMARK_LOOKUPS - tour_pairs.len()));
+  }
+
+  let matrix = &dataset.distance_matrix;
+  display_measure("dataset", memory_size(matrix), |i, j| matrix.get(i, j), &random_pairs, &tour_pairs);
+
+  // the baseline : a vector of rows, each one allocated separately
+  let rows: Vec<Vec<f64>> = (0..size).map(|i| (0..size).map(|j| matrix.get(i, j)).collect()).collect();
+  let rows_size = size * std::mem::size_of::<Vec<f64>>() + size * size * std::mem::size_of::<f64>();
+  display_measure("nested vectors", rows_size, |i, j| rows[i][j], &random_pairs, &tour_pairs);
+
+  // materialize the distances with each precision and layout
+  // the integer storage rejects fractional distances, so they are rounded first
+  let symmetric = matrix.is_symmetric();
+  for (precision, precision_name) in [(Precision::F64, "f64"), (Precision::F32, "f32"), (Precision::Integer, "integer")] {
+    for (layout, layout_name) in [(Layout::Full, "full"), (Layout::Triangular, "triangular")] {
+      if layout == Layout::Triangular && !symmetric {
+        continue;
+      }
+      let stored = if precision == Precision::Integer {
+        Matrix::from_fn(size, symmetric, precision, layout, |i, j| matrix.get(i, j).round())
+      } else {
+        Matrix::from_fn(size, symmetric, precision, layout, |i, j| matrix.get(i, j))
+      };
+      display_measure(&format!("{} {}", precision_name, layout_name), memory_size(&stored), |i, j| stored.get(i, j), &random_pairs, &tour_pairs);
+    }
+  }
+}
@@ -0,0 +1,287 @@
+use crate::dataset::{Dataset,Matrix};
+use crate::exact::TourKind;
+
+// number of subgradient iterations used for the Held-Karp bound
+pub const HELD_KARP_ITERATIONS: usize = 100;
+
+// define the lower bounds computed for a dataset
+pub struct LowerBounds {
+  // minimum 1-tree (or minimum spanning tree for open tours)
+  pub one_tree: f64,
+  // 1-tree improved with Held-Karp node penalties
+  pub held_karp: f64,
+  // assignment problem relaxation, only computed for asymmetric datasets
+  pub assignment: Option<f64>
+}
+
+impl LowerBounds {
+  // returns the tightest of the computed bounds
+  pub fn best(&self) -> f64 {
+    self.one_tree.max(self.held_karp).max(self.assignment.unwrap_or(f64::NEG_INFINITY))
+  }
+
+  // returns the optimality gap of a tour length in percent, None if the best bound is not positive
+  pub fn gap(&self, length: f64) -> Option<f64> {
+    let best = self.best();
+    if best > 0.0 { Some((length - best) / best * 100.0) } else { None }
+  }
+}
+
+// define a view on the dataset distances used to compute the bounds
+// open tours are turned into closed ones by adding a dummy node at distance 0 from every other node
+pub struct Costs<'a> {
+  matrix: &'a Matrix,
+  pub size: usize,
+  dummy: Option<usize>,
+  symmetric: bool
+}
+
+impl<'a> Costs<'a> {
+  pub fn new(matrix: &'a Matrix, kind: TourKind) -> Self {
+    let (size, dummy) = match kind {
+      TourKind::Open => (matrix.len() + 1, Some(matrix.len())),
+      TourKind::Closed => (matrix.len(), None)
+    };
+    Self { matrix, size, dummy, symmetric: matrix.is_symmetric() }
+  }
+
+  // distance from a node to another
+  pub fn get(&self, node1: usize, node2: usize) -> f64 {
+    if Some(node1) == self.dummy || Some(node2) == self.dummy {
+      0.0
+    } else {
+      self.matrix.get(node1, node2)
+    }
+  }
+
+  // cheapest direction of an edge, which is a valid cost for symmetric relaxations
+  pub fn undirected(&self, node1: usize, node2: usize) -> f64 {
+    if self.symmetric {
+      self.get(node1, node2)
+    } else {
+      self.get(node1, node2).min(self.get(node2, node1))
+    }
+  }
+}
+
+// compute a minimum spanning tree of the nodes 0..size using Prim's algorithm, in O(n^2)
+// returns the edges of the tree as (parent, child, cost)
+pub fn minimum_spanning_tree<F: Fn(usize, usize) -> f64>(size: usize, cost: F) -> Vec<(usize, usize, f64)> {
+  let mut edges = Vec::with_capacity(size.saturating_sub(1));
+  if size == 0 {
+    return edges;
+  }
+
+  let mut in_tree = vec![false; size];
+  let mut connection: Vec<(f64, usize)> = (0..size).map(|node| (cost(0, node), 0)).collect();
+  in_tree[0] = true;
+  for _ in 1..size {
+    // find the closest node to the tree
+    let mut closest: Option<usize> = None;
+    for node in 0..size {
+      if !in_tree[node] && (closest.is_none() || connection[node].0 < connection[closest.unwrap()].0) {
+        closest = Some(node);
+      }
+    }
+    let closest = closest.expect("Unable to find the closest node while computing the spanning tree");
+
+    // add it to the tree and update the connections
+    let (closest_cost, parent) = connection[closest];
+    in_tree[closest] = true;
+    edges.push((parent, closest, closest_cost));
+    for node in 0..size {
+      if !in_tree[node] {
+        let node_cost = cost(closest, node);
+        if node_cost < connection[node].0 {
+          connection[node] = (node_cost, closest);
+        }
+      }
+    }
+  }
+
+  edges
+}
+
+// define a minimum 1-tree: a spanning tree of every node but the special (last) one,
+// plus the two cheapest edges connecting the special node
+pub struct OneTree {
+  pub weight: f64,
+  pub tree_edges: Vec<(usize, usize, f64)>,
+  pub special_edges: Vec<(usize, f64)>,
+  pub degrees: Vec<usize>
+}
+
+// compute the minimum 1-tree of the costs modified by the node penalties
+pub fn minimum_one_tree(costs: &Costs, penalties: &[f64]) -> OneTree {
+  let special = costs.size - 1;
+  let modified = |node1: usize, node2: usize| costs.undirected(node1, node2) + penalties[node1] + penalties[node2];
+  let mut degrees = vec![0; costs.size];
+
+  // build the spanning tree of every node but the special one
+  let tree_edges = minimum_spanning_tree(special, modified);
+  for &(parent, child, _) in tree_edges.iter() {
+    degrees[parent] += 1;
+    degrees[child] += 1;
+  }
+
+  // connect the special node with its two cheapest edges
+  let mut special_edges: Vec<(usize, f64)> = (0..special).map(|node| (node, modified(special, node))).collect();
+  special_edges.sort_by(|e1, e2| e1.1.partial_cmp(&e2.1).expect("Error while sorting the 1-tree special edges"));
+  special_edges.truncate(2);
+  for &(node, _) in special_edges.iter() {
+    degrees[special] += 1;
+    degrees[node] += 1;
+  }
+
+  let weight = tree_edges.iter().map(|edge| edge.2).sum::<f64>() + special_edges.iter().map(|edge| edge.1).sum::<f64>();
+  OneTree { weight, tree_edges, special_edges, degrees }
+}
+
+// compute the minimum 1-tree bound
+pub fn one_tree_bound(dataset: &Dataset, kind: TourKind) -> f64 {
+  let costs = Costs::new(&dataset.distance_matrix, kind);
+  minimum_one_tree(&costs, &vec![0.0; costs.size]).weight
+}
+
+// optimize the node penalties by subgradient ascent to maximize the 1-tree bound
+// the upper bound (length of a known tour, or an estimate) is used to size the steps
+// returns the best bound found and the penalties giving it
+pub fn subgradient_ascent(costs: &Costs, upper_bound: f64, iterations: usize) -> (f64, Vec<f64>) {
+  let mut penalties = vec![0.0; costs.size];
+  let mut best_bound = f64::NEG_INFINITY;
+  let mut best_penalties = penalties.clone();
+
+  // the step scale is halved each time the bound stops improving for a while
+  let mut scale = 2.0;
+  let mut iterations_without_improvement = 0;
+
+  for _ in 0..iterations {
+    let one_tree = minimum_one_tree(costs, &penalties);
+    let bound = one_tree.weight - 2.0 * penalties.iter().sum::<f64>();
+
+    if bound > best_bound {
+      best_bound = bound;
+      best_penalties.clone_from(&penalties);
+      iterations_without_improvement = 0;
+    } else {
+      iterations_without_improvement += 1;
+      if iterations_without_improvement >= 10 {
+        scale /= 2.0;
+        iterations_without_improvement = 0;
+      }
+    }
+
+    // a 1-tree where every node has a degree of 2 is an optimal tour
+    let norm: f64 = one_tree.degrees.iter().map(|&degree| (degree as f64 - 2.0).powi(2)).sum();
+    if norm == 0.0 {
+      break;
+    }
+
+    // move the penalties toward the nodes with a wrong degree
+    let step = scale * (upper_bound - bound).max(0.0) / norm;
+    if step < 1e-9 {
+      break;
+    }
+    for (penalty, &degree) in penalties.iter_mut().zip(one_tree.degrees.iter()) {
+      *penalty += step * (degree as f64 - 2.0);
+    }
+  }
+
+  (best_bound, best_penalties)
+}
+
+// compute the Held-Karp bound by subgradient optimization of the node penalties
+pub fn held_karp_bound(dataset: &Dataset, kind: TourKind, upper_bound: f64, iterations: usize) -> f64 {
+  let costs = Costs::new(&dataset.distance_matrix, kind);
+  subgradient_ascent(&costs, upper_bound, iterations).0
+}
+
+// compute the assignment bound using the Hungarian algorithm, in O(n^3)
+// each node gets exactly one successor, but subtours are allowed
+pub fn assignment_bound(dataset: &Dataset, kind: TourKind) -> f64 {
+  let costs = Costs::new(&dataset.distance_matrix, kind);
+  let size = costs.size;
+
+  // a node can't be its own successor
+  let cost = |node1: usize, node2: usize| if node1 == node2 { f64::INFINITY } else { costs.get(node1, node2) };
+
+  // potentials of rows and columns, matching of columns (indices are shifted by one, 0 is a sentinel)
+  let mut row_potentials = vec![0.0; size + 1];
+  let mut column_potentials = vec![0.0; size + 1];
+  let mut matching = vec![0; size + 1];
+  let mut way = vec![0; size + 1];
+
+  for row in 1..=size {
+    matching[0] = row;
+    let mut column = 0;
+    let mut min_values = vec![f64::INFINITY; size + 1];
+    let mut used = vec![false; size + 1];
+
+    // find an augmenting path
+    loop {
+      used[column] = true;
+      let matched_row = matching[column];
+      let mut delta = f64::INFINITY;
+      let mut next_column = 0;
+
+      for candidate in 1..=size {
+        if used[candidate] {
+          continue;
+        }
+        let reduced = cost(matched_row - 1, candidate - 1) - row_potentials[matched_row] - column_potentials[candidate];
+        if reduced < min_values[candidate] {
+          min_values[candidate] = reduced;
+          way[candidate] = column;
+        }
+        if min_values[candidate] < delta {
+          delta = min_values[candidate];
+          next_column = candidate;
+        }
+      }
+
+      for candidate in 0..=size {
+        if used[candidate] {
+          row_potentials[matching[candidate]] += delta;
+          column_potentials[candidate] -= delta;
+        } else {
+          min_values[candidate] -= delta;
+        }
+      }
+
+      column = next_column;
+      if matching[column] == 0 {
+        break;
+      }
+    }
+
+    // apply the augmenting path
+    loop {
+      let previous_column = way[column];
+      matching[column] = matching[previous_column];
+      column = previous_column;
+      if column == 0 {
+        break;
+      }
+    }
+  }
+
+  (1..=size).map(|column| cost(matching[column] - 1, column - 1)).sum()
+}
+
+// verify that the lower bounds can be computed for a dataset
+pub fn verify_supported(dataset: &Dataset) {
+  if dataset.time_windows.is_some() || dataset.fleet.is_some() || dataset.orienteering.is_some() || dataset.clusters.is_some() || dataset.node_costs.is_some() {
+    panic!("The lower bounds can't be computed with time windows, several salesmen, prizes, clusters or node costs");
+  }
+}
+
+// compute every lower bound relevant for the dataset
+pub fn compute(dataset: &Dataset, kind: TourKind, upper_bound: f64) -> LowerBounds {
+  verify_supported(dataset);
+
+  LowerBounds {
+    one_tree: one_tree_bound(dataset, kind),
+    held_karp: held_karp_bound(dataset, kind, upper_bound, HELD_KARP_ITERATIONS),
+    assignment: if dataset.distance_matrix.is_symmetric() { None } else { Some(assignment_bound(dataset, kind)) }
+  }
+}
@@ -0,0 +1,355 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use clap::ValueEnum;
+use crate::bounds::{Costs,minimum_one_tree,subgradient_ascent};
+use crate::dataset::{Matrix,NeighborsMatrix};
+use crate::exact::TourKind;
+use crate::spatial::KdTree;
+
+// number of subgradient iterations used to compute the penalties of the alpha-nearness
+pub const ALPHA_ASCENT_ITERATIONS: usize = 20;
+
+// define the ways to choose the candidate neighbors of each node
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CandidateSet {
+  // the closest nodes
+  Nearest,
+  // the nodes with the lowest 1-tree alpha-nearness (as in LKH), in O(n^2) time so only for small datasets
+  Alpha,
+  // the closest nodes in each of the four quadrants around the node (locations only)
+  Quadrant,
+  // the neighbors in the Delaunay triangulation of the locations (locations only)
+  Delaunay
+}
+
+// sort nodes by increasing score, the distance breaking ties
+fn sort_by_score(neighbors: &mut [(f64, f64, usize)]) {
+  neighbors.sort_by(|n1, n2| {
+    n1.0.partial_cmp(&n2.0)
+      .and_then(|ordering| if ordering == Ordering::Equal { n1.1.partial_cmp(&n2.1) } else { Some(ordering) })
+      .expect("Error while sorting candidates")
+  });
+}
+
+// find the candidates of each node with the lowest alpha-nearness
+// the alpha-nearness of an edge is the increase of the minimum 1-tree weight when the edge is forced into it,
+// so edges of optimal tours usually have a small alpha even when they aren't among the shortest ones
+// the node penalties are first optimized by subgradient ascent, as in LKH
+pub fn alpha_nearness(matrix: &Matrix, candidates_count: usize) -> NeighborsMatrix {
+  let costs = Costs::new(matrix, TourKind::Closed);
+  let size = costs.size;
+
+  // an estimate of the tour length is enough to size the subgradient steps
+  let estimate = minimum_one_tree(&costs, &vec![0.0; size]).weight * 1.1;
+  let (_, penalties) = subgradient_ascent(&costs, estimate, ALPHA_ASCENT_ITERATIONS);
+  let modified = |node1: usize, node2: usize| costs.undirected(node1, node2) + penalties[node1] + penalties[node2];
+
+  // build the adjacency of the 1-tree
+  let one_tree = minimum_one_tree(&costs, &penalties);
+  let special = size - 1;
+  let mut adjacency: Vec<Vec<(usize, f64)>> = vec![Vec::new(); size];
+  for &(parent, child, cost) in one_tree.tree_edges.iter() {
+    adjacency[parent].push((child, cost));
+    adjacency[child].push((parent, cost));
+  }
+
+  // an edge of the special node can replace the most expensive of its 1-tree edges
+  let special_replaced = one_tree.special_edges.iter().map(|edge| edge.1).fold(f64::NEG_INFINITY, f64::max);
+  let special_alpha = |node: usize| {
+    if one_tree.special_edges.iter().any(|edge| edge.0 == node) { 0.0 } else { modified(special, node) - special_replaced }
+  };
+
+  let mut nodes_neighbors: NeighborsMatrix = Vec::with_capacity(size);
+  let mut beta = vec![0.0; size];
+  let mut stack = Vec::with_capacity(size);
+  let mut visited = vec![false; size];
+  for node in 0..size {
+    let mut neighbors: Vec<(f64, f64, usize)> = Vec::with_capacity(size - 1);
+
+    if node == special {
+      neighbors.extend((0..special).map(|neighbor| (special_alpha(neighbor), matrix.get(node, neighbor), neighbor)));
+    } else {
+      // compute the most expensive edge on the tree path from the node to every other node
+      visited.iter_mut().for_each(|v| *v = false);
+      visited[node] = true;
+      beta[node] = f64::NEG_INFINITY;
+      stack.push(node);
+      while let Some(current) = stack.pop() {
+        for &(next, cost) in adjacency[current].iter() {
+          if !visited[next] {
+            visited[next] = true;
+            beta[next] = f64::max(beta[current], cost);
+            stack.push(next);
+          }
+        }
+      }
+
+      // forcing an edge in the tree removes the most expensive edge of the cycle it creates
+      for neighbor in (0..special).filter(|&neighbor| neighbor != node) {
+        neighbors.push((modified(node, neighbor) - beta[neighbor], matrix.get(node, neighbor), neighbor));
+      }
+      neighbors.push((special_alpha(node), matrix.get(node, special), special));
+    }
+
+    // keep the candidates with the lowest alpha
+    let count = candidates_count.min(neighbors.len());
+    if count < neighbors.len() {
+      neighbors.select_nth_unstable_by(count - 1, |n1, n2| n1.0.partial_cmp(&n2.0).expect("Error while selecting candidates"));
+      neighbors.truncate(count);
+    }
+    sort_by_score(&mut neighbors);
+    nodes_neighbors.push(neighbors.into_iter().map(|(_, _, neighbor)| neighbor).collect());
+  }
+
+  nodes_neighbors
+}
+
+// find the closest candidates of each node in each quadrant around it
+// if some quadrants don't have enough nodes, the closest remaining nodes are added
+pub fn quadrant_neighbors(locations: &[(f64, f64)], spatial_index: &KdTree, candidates_count: usize) -> NeighborsMatrix {
+  let quadrant = |origin: (f64, f64), location: (f64, f64)| match (location.0 >= origin.0, location.1 >= origin.1) {
+    (true, true) => 0,
+    (false, true) => 1,
+    (false, false) => 2,
+    (true, false) => 3
+  };
+  let per_quadrant = candidates_count.div_ceil(4);
+
+  (0..locations.len()).map(|node| {
+    let origin = locations[node];
+    let mut neighbors: Vec<usize> = Vec::with_capacity(candidates_count);
+    for q in 0..4 {
+      neighbors.extend(spatial_index.k_nearest(origin, per_quadrant, |neighbor| neighbor != node && quadrant(origin, locations[neighbor]) == q));
+    }
+
+    // complete with the closest nodes
+    if neighbors.len() < candidates_count {
+      for neighbor in spatial_index.k_nearest(origin, candidates_count, |neighbor| neighbor != node) {
+        if neighbors.len() < candidates_count && !neighbors.contains(&neighbor) {
+          neighbors.push(neighbor);
+        }
+      }
+    }
+
+    // sort them by distance
+    let distance = |neighbor: usize| (locations[neighbor].0 - origin.0).powi(2) + (locations[neighbor].1 - origin.1).powi(2);
+    neighbors.sort_by(|&n1, &n2| distance(n1).partial_cmp(&distance(n2)).expect("Error while sorting candidates"));
+    neighbors.truncate(candidates_count);
+    neighbors
+  }).collect()
+}
+
+// below this ratio between the area of a triangle and the squared length of its edges, the triangle is considered flat
+const FLAT_TRIANGLE_TOLERANCE: f64 = 1e-12;
+
+// define a triangle of the Delaunay triangulation, with its circumscribed circle
+// its vertices are in counterclockwise order
+struct Triangle {
+  vertices: [usize; 3],
+  center: (f64, f64),
+  squared_radius: f64,
+  removed: bool
+}
+
+impl Triangle {
+  // returns None for flat (collinear vertices) or clockwise triangles, whose circumscribed circle can't be computed reliably
+  fn new(vertices: [usize; 3], points: &[(f64, f64)]) -> Option<Self> {
+    let (a, b, c) = (points[vertices[0]], points[vertices[1]], points[vertices[2]]);
+    let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+    let squared_edges = (b.0 - a.0).powi(2) + (b.1 - a.1).powi(2) + (c.0 - b.0).powi(2) + (c.1 - b.1).powi(2) + (a.0 - c.0).powi(2) + (a.1 - c.1).powi(2);
+    if d < FLAT_TRIANGLE_TOLERANCE * squared_edges {
+      return None;
+    }
+
+    let a2 = a.0*a.0 + a.1*a.1;
+    let b2 = b.0*b.0 + b.1*b.1;
+    let c2 = c.0*c.0 + c.1*c.1;
+    let center = (
+      (a2 * (b.1 - c.1) + b2 * (c.1 - a.1) + c2 * (a.1 - b.1)) / d,
+      (a2 * (c.0 - b.0) + b2 * (a.0 - c.0) + c2 * (b.0 - a.0)) / d
+    );
+    let squared_radius = (a.0 - center.0).powi(2) + (a.1 - center.1).powi(2);
+    Some(Self { vertices, center, squared_radius, removed: false })
+  }
+
+  fn circumcircle_contains(&self, point: (f64, f64)) -> bool {
+    (point.0 - self.center.0).powi(2) + (point.1 - self.center.1).powi(2) < self.squared_radius
+  }
+
+  // returns the edges of the triangle, in counterclockwise order
+  fn edges(&self) -> [(usize, usize); 3] {
+    [(self.vertices[0], self.vertices[1]), (self.vertices[1], self.vertices[2]), (self.vertices[2], self.vertices[0])]
+  }
+}
+
+// define a Delaunay triangulation built by inserting the points one by one (Bowyer-Watson algorithm)
+// each directed edge is mapped to the triangle it belongs to, the triangle across it owning the reversed edge
+struct Triangulation<'a> {
+  points: &'a [(f64, f64)],
+  triangles: Vec<Triangle>,
+  edges: HashMap<(usize, usize), usize>
+}
+
+impl<'a> Triangulation<'a> {
+  // add a triangle, returns None if it is flat
+  fn add(&mut self, vertices: [usize; 3]) -> Option<()> {
+    let triangle = Triangle::new(vertices, self.points)?;
+    for edge in triangle.edges() {
+      self.edges.insert(edge, self.triangles.len());
+    }
+    self.triangles.push(triangle);
+    Some(())
+  }
+
+  // find a triangle whose circumscribed circle contains the point, walking from the last added triangle toward the point
+  // points inserted close to the previous one are found in a few steps
+  fn locate(&self, point: (f64, f64)) -> Option<usize> {
+    let side = |(v1, v2): (usize, usize)| {
+      let (a, b) = (self.points[v1], self.points[v2]);
+      (b.0 - a.0) * (point.1 - a.1) - (b.1 - a.1) * (point.0 - a.0)
+    };
+
+    let mut current = self.triangles.len() - 1;
+    for _ in 0..self.triangles.len() {
+      // move to the triangle across the first edge having the point on its other side
+      match self.triangles[current].edges().into_iter().find(|&edge| side(edge) < 0.0) {
+        Some((v1, v2)) => match self.edges.get(&(v2, v1)) {
+          Some(&next) => current = next,
+          None => break
+        },
+        None if self.triangles[current].circumcircle_contains(point) => return Some(current),
+        None => break
+      }
+    }
+
+    // rounding errors can make the walk loop, every triangle is then checked
+    (0..self.triangles.len()).find(|&triangle| !self.triangles[triangle].removed && self.triangles[triangle].circumcircle_contains(point))
+  }
+
+  // insert a point, removing the triangles whose circumscribed circle contains it and connecting it to the boundary of the hole
+  // returns None if a flat triangle would be created
+  fn insert(&mut self, node: usize) -> Option<()> {
+    let point = self.points[node];
+    let first = self.locate(point)?;
+
+    // the removed triangles are connected, so they are found from the first one
+    let mut removed = vec![first];
+    let mut stack = vec![first];
+    self.triangles[first].removed = true;
+    while let Some(triangle) = stack.pop() {
+      for (v1, v2) in self.triangles[triangle].edges() {
+        if let Some(&next) = self.edges.get(&(v2, v1)) {
+          if !self.triangles[next].removed && self.triangles[next].circumcircle_contains(point) {
+            self.triangles[next].removed = true;
+            removed.push(next);
+            stack.push(next);
+          }
+        }
+      }
+    }
+
+    // the edges of the hole are the edges of removed triangles without a removed triangle across them
+    let mut boundary = Vec::new();
+    for &triangle in removed.iter() {
+      for (v1, v2) in self.triangles[triangle].edges() {
+        if self.edges.get(&(v2, v1)).is_none_or(|&next| !self.triangles[next].removed) {
+          boundary.push((v1, v2));
+        }
+      }
+    }
+    for &triangle in removed.iter() {
+      for edge in self.triangles[triangle].edges() {
+        self.edges.remove(&edge);
+      }
+    }
+    for (v1, v2) in boundary {
+      self.add([v1, v2, node])?;
+    }
+    Some(())
+  }
+}
+
+// find the candidates of each node among its neighbors in the Delaunay triangulation
+// the triangulation usually gives around 6 neighbors per node, the closest ones are kept
+// if the triangulation fails because of (nearly) collinear points, the nearest nodes are used instead
+pub fn delaunay_neighbors(locations: &[(f64, f64)], spatial_index: &KdTree, candidates_count: usize) -> NeighborsMatrix {
+  match delaunay_triangulation(locations) {
+    Some(mut neighbors) => {
+      // sort them by distance and keep the closest ones
+      for (node, node_neighbors) in neighbors.iter_mut().enumerate() {
+        let origin = locations[node];
+        let distance = |neighbor: usize| (locations[neighbor].0 - origin.0).powi(2) + (locations[neighbor].1 - origin.1).powi(2);
+        node_neighbors.sort_by(|&n1, &n2| distance(n1).partial_cmp(&distance(n2)).expect("Error while sorting candidates"));
+        node_neighbors.truncate(candidates_count);
+      }
+      neighbors
+    },
+    None => (0..locations.len())
+      .map(|node| spatial_index.k_nearest(locations[node], candidates_count, |neighbor| neighbor != node))
+      .collect()
+  }
+}
+
+// returns the neighbors of each node in the Delaunay triangulation of the locations, or None if it creates a flat triangle
+// the points are inserted along a snake path through a grid of cells, so each point is close to the previous one
+fn delaunay_triangulation(locations: &[(f64, f64)]) -> Option<Vec<Vec<usize>>> {
+  let size = locations.len();
+
+  // add a super triangle containing every location
+  let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+  for &(x, y) in locations.iter() {
+    min_x = min_x.min(x);
+    min_y = min_y.min(y);
+    max_x = max_x.max(x);
+    max_y = max_y.max(y);
+  }
+  let extent = (max_x - min_x).max(max_y - min_y).max(1.0) * 20.0;
+  let (center_x, center_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+  let mut points = locations.to_vec();
+  points.push((center_x - extent, center_y - extent));
+  points.push((center_x + extent, center_y - extent));
+  points.push((center_x, center_y + extent));
+  let mut triangulation = Triangulation { points: &points, triangles: Vec::with_capacity(4 * size), edges: HashMap::new() };
+  triangulation.add([size, size + 1, size + 2])?;
+
+  // sort the points by cell, every other row of cells being read backward
+  let cells = (size as f64).sqrt().ceil().max(1.0);
+  let cell = |value: f64, min: f64, max: f64| (((value - min) / (max - min).max(f64::MIN_POSITIVE) * cells) as usize).min(cells as usize - 1);
+  let mut order: Vec<(usize, usize, usize)> = (0..size)
+    .map(|node| {
+      let (row, column) = (cell(locations[node].1, min_y, max_y), cell(locations[node].0, min_x, max_x));
+      (row, if row % 2 == 0 { column } else { cells as usize - 1 - column }, node)
+    })
+    .collect();
+  order.sort_unstable();
+
+  // duplicated locations are not inserted, they share the neighbors of the first one
+  let mut first_occurrences: HashMap<(u64, u64), usize> = HashMap::new();
+  let mut duplicates: Vec<(usize, usize)> = Vec::new();
+
+  for (_, _, node) in order {
+    let point = points[node];
+    if let Some(&original) = first_occurrences.get(&(point.0.to_bits(), point.1.to_bits())) {
+      duplicates.push((node, original));
+      continue;
+    }
+    first_occurrences.insert((point.0.to_bits(), point.1.to_bits()), node);
+    triangulation.insert(node)?;
+  }
+
+  // collect the neighbors of each node, ignoring the super triangle
+  let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); size];
+  for &(v1, v2) in triangulation.edges.keys() {
+    if v1 < size && v2 < size && !neighbors[v1].contains(&v2) {
+      neighbors[v1].push(v2);
+      neighbors[v2].push(v1);
+    }
+  }
+  for &(duplicate, original) in duplicates.iter() {
+    neighbors[duplicate] = neighbors[original].clone();
+    neighbors[duplicate].insert(0, original);
+    neighbors[original].insert(0, duplicate);
+  }
+
+  Some(neighbors)
+}
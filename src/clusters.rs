@@ -0,0 +1,103 @@
+use rand::prelude::*;
+use crate::dataset::{LabelIndex,Labels,NeighborsMatrix};
+
+// define the clusters of a generalized traveling salesman problem : exactly one node of each cluster is visited
+// the nodes of an individual are a permutation of every node, each cluster being represented by its first node,
+// so the order of the permutation chooses both the order of the clusters and their representatives
+// the representatives are moved to the front of the nodes, in order, and are the only visited ones
+pub struct Clusters {
+  // cluster of each node
+  cluster_of: Vec<usize>,
+  members: Vec<Vec<usize>>
+}
+
+impl Clusters {
+  // build the clusters of a dataset from lists of labels, each node left out being a cluster on its own
+  // returns None if there are no clusters
+  pub fn new(labels: &Labels, clusters: &[Vec<String>]) -> Option<Self> {
+    if clusters.is_empty() {
+      return None;
+    }
+
+    let label_index = LabelIndex::new(labels);
+    let mut cluster_of: Vec<Option<usize>> = vec![None; labels.len()];
+    let mut members: Vec<Vec<usize>> = Vec::with_capacity(clusters.len());
+    for cluster in clusters.iter() {
+      if cluster.is_empty() {
+        panic!("The clusters should not be empty");
+      }
+      let mut cluster_members = Vec::with_capacity(cluster.len());
+      for label in cluster.iter() {
+        let node = label_index.get(label).unwrap_or_else(|| panic!("Unknown label {} in the clusters", label));
+        if cluster_of[node].is_some() {
+          panic!("{} can't be in several clusters", label);
+        }
+        cluster_of[node] = Some(members.len());
+        cluster_members.push(node);
+      }
+      members.push(cluster_members);
+    }
+
+    // the nodes left out are visited anyway
+    for (node, cluster) in cluster_of.iter_mut().enumerate() {
+      if cluster.is_none() {
+        *cluster = Some(members.len());
+        members.push(vec![node]);
+      }
+    }
+
+    let cluster_of = cluster_of.into_iter().map(|cluster| cluster.expect("Unable to find the cluster of a node")).collect();
+    Some(Self { cluster_of, members })
+  }
+
+  // returns the number of clusters, which is the number of visited nodes
+  pub fn count(&self) -> usize {
+    self.members.len()
+  }
+
+  // returns the clusters visited several times or not visited by the given visited nodes, as lists of labels
+  pub fn violations(&self, labels: &Labels, visited_nodes: &[usize]) -> Vec<String> {
+    let mut visits = vec![0; self.members.len()];
+    for &node in visited_nodes.iter() {
+      visits[self.cluster_of[node]] += 1;
+    }
+    let cluster_labels = |cluster: usize| self.members[cluster].iter().map(|&node| labels[node].as_str()).collect::<Vec<&str>>().join(", ");
+    visits.iter().enumerate().filter(|&(_, &count)| count != 1)
+      .map(|(cluster, &count)| format!("the cluster [{}] is visited {} times instead of once", cluster_labels(cluster), count))
+      .collect()
+  }
+
+  // move the first node of each cluster to the front of the nodes, keeping the order of the others
+  pub fn normalize(&self, nodes: &mut Vec<usize>) {
+    let mut represented = vec![false; self.members.len()];
+    let (representatives, others): (Vec<usize>, Vec<usize>) = nodes.iter().partition(|&&node| {
+      let cluster = self.cluster_of[node];
+      !std::mem::replace(&mut represented[cluster], true)
+    });
+    nodes.clear();
+    nodes.extend(representatives);
+    nodes.extend(others);
+  }
+
+  // replace a random representative by another node of its cluster, preferably a candidate neighbor of the previous visited node
+  pub fn switch(&self, rng: &mut ThreadRng, nodes_neighbors: &NeighborsMatrix, nodes: &mut [usize]) {
+    let index = rng.gen_range(0..self.members.len());
+    let cluster = self.cluster_of[nodes[index]];
+    if self.members[cluster].len() < 2 {
+      return;
+    }
+
+    let candidates: Vec<usize> = match index {
+      0 => Vec::new(),
+      _ => nodes_neighbors[nodes[index - 1]].iter().copied().filter(|&neighbor| neighbor != nodes[index] && self.cluster_of[neighbor] == cluster).collect()
+    };
+    let replacement = match candidates.choose(rng) {
+      Some(&neighbor) => neighbor,
+      None => *self.members[cluster].iter().filter(|&&node| node != nodes[index]).choose(rng).expect("Unable to find another node in the cluster")
+    };
+
+    // the replacement isn't visited, so it is after the representatives
+    let position = nodes.iter().position(|&node| node == replacement).expect("Unable to find the replacement node");
+    nodes.swap(index, position);
+  }
+}
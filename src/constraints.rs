@@ -0,0 +1,256 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use crate::dataset::{LabelIndex,Labels,Matrix};
+
+// returns the node of a label used by a constraint
+fn find_node(label_index: &LabelIndex, label: &str) -> usize {
+  label_index.get(label).unwrap_or_else(|| panic!("Unknown label {} in the constraints", label))
+}
+
+// define the constraints on the order in which the nodes are visited
+// fixed edges must be used by every tour, they are grouped into fixed segments (chains of fixed edges)
+// precedences force a node to be visited before another one, not necessarily right before
+pub struct Constraints {
+  fixed_successors: Vec<Option<usize>>,
+  fixed_predecessors: Vec<Option<usize>>,
+  pub segments: Vec<Vec<usize>>,
+  // segment containing each node, if any
+  segment_of: Vec<Option<usize>>,
+  pub precedences: Vec<(usize, usize)>,
+  // nodes that must be visited before each node
+  required_before: Vec<Vec<usize>>,
+  // precedences between units (segments or nodes outside of segments), each unit being identified by its first node
+  unit_successors: Vec<Vec<usize>>,
+  unit_predecessors_counts: Vec<usize>
+}
+
+impl Constraints {
+  // build empty constraints, every tour respecting them
+  pub fn none(labels: &Labels) -> Self {
+    Self::new(labels, &[], &[], &[])
+  }
+
+  // build the constraints from the fixed edges, fixed segments and precedences of a dataset, given by labels
+  // panics if the fixed edges don't form simple paths, or if the precedences can't be respected
+  pub fn new(labels: &Labels, fixed_edges: &[(String, String)], fixed_segments: &[Vec<String>], precedences: &[(String, String)]) -> Self {
+    let size = labels.len();
+    let label_index = LabelIndex::new(labels);
+    let mut fixed_successors: Vec<Option<usize>> = vec![None; size];
+    let mut fixed_predecessors: Vec<Option<usize>> = vec![None; size];
+
+    // a segment is a list of fixed edges
+    let edges = fixed_edges.iter()
+      .map(|(label1, label2)| (label1.as_str(), label2.as_str()))
+      .chain(fixed_segments.iter().flat_map(|segment| segment.windows(2).map(|edge| (edge[0].as_str(), edge[1].as_str()))));
+
+    // each node can have only one fixed successor and one fixed predecessor
+    for (label1, label2) in edges {
+      let (node1, node2) = (find_node(&label_index, label1), find_node(&label_index, label2));
+      if node1 == node2 {
+        panic!("The fixed edges should form simple paths : {} can't be followed by itself", label1);
+      }
+      if let Some(successor) = fixed_successors[node1].filter(|&successor| successor != node2) {
+        panic!("The fixed edges should form simple paths : {} can't be followed by both {} and {}", label1, labels[successor], label2);
+      }
+      if let Some(predecessor) = fixed_predecessors[node2].filter(|&predecessor| predecessor != node1) {
+        panic!("The fixed edges should form simple paths : {} can't be preceded by both {} and {}", label2, labels[predecessor], label1);
+      }
+      fixed_successors[node1] = Some(node2);
+      fixed_predecessors[node2] = Some(node1);
+    }
+
+    // follow the fixed edges from the first node of each segment
+    let mut segments: Vec<Vec<usize>> = Vec::new();
+    let mut segment_of: Vec<Option<usize>> = vec![None; size];
+    for first in (0..size).filter(|&node| fixed_successors[node].is_some() && fixed_predecessors[node].is_none()) {
+      let mut segment = vec![first];
+      while let Some(next) = fixed_successors[segment[segment.len() - 1]] {
+        segment.push(next);
+      }
+      for &node in segment.iter() {
+        segment_of[node] = Some(segments.len());
+      }
+      segments.push(segment);
+    }
+
+    // the nodes with fixed edges that aren't in a segment are in a cycle
+    if let Some(node) = (0..size).find(|&node| fixed_successors[node].is_some() && segment_of[node].is_none()) {
+      panic!("The fixed edges should form simple paths : they form a cycle going through {}", labels[node]);
+    }
+
+    // link the units of the nodes of each precedence
+    let unit = |node: usize| segment_of[node].map_or(node, |segment| segments[segment][0]);
+    let precedences: Vec<(usize, usize)> = precedences.iter().map(|(label1, label2)| (find_node(&label_index, label1), find_node(&label_index, label2))).collect();
+    let mut required_before: Vec<Vec<usize>> = vec![Vec::new(); size];
+    let mut unit_successors: Vec<Vec<usize>> = vec![Vec::new(); size];
+    let mut unit_predecessors_counts = vec![0; size];
+    for &(before, after) in precedences.iter() {
+      if before == after {
+        panic!("{} can't be visited before itself", labels[before]);
+      }
+      required_before[after].push(before);
+
+      // inside a segment, the order is already fixed
+      if unit(before) == unit(after) {
+        let segment = &segments[segment_of[before].expect("Unable to find the segment of a node")];
+        if segment.iter().position(|&node| node == before) > segment.iter().position(|&node| node == after) {
+          panic!("The precedences conflict with the fixed edges : {} should be visited before {}", labels[before], labels[after]);
+        }
+      } else if !unit_successors[unit(before)].contains(&unit(after)) {
+        unit_successors[unit(before)].push(unit(after));
+        unit_predecessors_counts[unit(after)] += 1;
+      }
+    }
+
+    let units: Vec<usize> = (0..size).filter(|&node| unit(node) == node).collect();
+    let constraints = Self { fixed_successors, fixed_predecessors, segments, segment_of, precedences, required_before, unit_successors, unit_predecessors_counts };
+
+    // the precedences can be respected if every unit can be ordered
+    if !constraints.precedences.is_empty() {
+      let ordered = constraints.order_units(&units);
+      if ordered.len() < units.len() {
+        let node = units.iter().find(|unit| !ordered.contains(unit)).expect("Unable to find an unordered node");
+        panic!("The precedences form a cycle : {} can never be visited", labels[*node]);
+      }
+    }
+
+    constraints
+  }
+
+  // verify that the fixed edges can be used
+  pub fn verify(&self, labels: &Labels, distance_matrix: &Matrix) {
+    for segment in self.segments.iter() {
+      for edge in segment.windows(2) {
+        if !distance_matrix.get(edge[0], edge[1]).is_finite() {
+          panic!("The fixed edge from {} to {} is forbidden", labels[edge[0]], labels[edge[1]]);
+        }
+      }
+    }
+  }
+
+  pub fn fixed_successor(&self, node: usize) -> Option<usize> {
+    self.fixed_successors[node]
+  }
+
+  pub fn fixed_predecessor(&self, node: usize) -> Option<usize> {
+    self.fixed_predecessors[node]
+  }
+
+  // returns the nodes that must be visited before a node
+  pub fn required_before(&self, node: usize) -> &[usize] {
+    &self.required_before[node]
+  }
+
+  // order the units so that every precedence is respected, as close as possible to their current order (Kahn's algorithm)
+  // the units left out are part of a cycle of precedences
+  fn order_units(&self, units: &[usize]) -> Vec<usize> {
+    let mut positions = vec![0; self.unit_successors.len()];
+    for (position, &unit) in units.iter().enumerate() {
+      positions[unit] = position;
+    }
+
+    // the first available unit of the current order is always taken
+    let mut predecessors_counts = self.unit_predecessors_counts.clone();
+    let mut available: BinaryHeap<Reverse<(usize, usize)>> = units.iter()
+      .filter(|&&unit| predecessors_counts[unit] == 0)
+      .map(|&unit| Reverse((positions[unit], unit)))
+      .collect();
+    let mut ordered = Vec::with_capacity(units.len());
+    while let Some(Reverse((_, unit))) = available.pop() {
+      ordered.push(unit);
+      for &successor in self.unit_successors[unit].iter() {
+        predecessors_counts[successor] -= 1;
+        if predecessors_counts[successor] == 0 {
+          available.push(Reverse((positions[successor], successor)));
+        }
+      }
+    }
+    ordered
+  }
+
+  // reorder the nodes of a tour so that it uses every fixed edge and respects every precedence
+  // each segment is visited in order where the tour first reaches one of its nodes,
+  // then the nodes visited too early are moved after the nodes they must follow
+  pub fn repair(&self, nodes: &mut Vec<usize>) {
+    if self.segments.is_empty() && self.precedences.is_empty() {
+      return;
+    }
+
+    let mut visited = vec![false; nodes.len()];
+    let mut repaired = Vec::with_capacity(nodes.len());
+    for &node in nodes.iter() {
+      if visited[node] {
+        continue;
+      }
+      match self.segment_of[node] {
+        Some(segment) => {
+          for &segment_node in self.segments[segment].iter() {
+            visited[segment_node] = true;
+            repaired.push(segment_node);
+          }
+        },
+        None => {
+          visited[node] = true;
+          repaired.push(node);
+        }
+      }
+    }
+
+    if !self.precedences.is_empty() {
+      let units: Vec<usize> = repaired.iter().copied().filter(|&node| self.fixed_predecessors[node].is_none()).collect();
+      repaired.clear();
+      for unit in self.order_units(&units) {
+        match self.segment_of[unit] {
+          Some(segment) => repaired.extend_from_slice(&self.segments[segment]),
+          None => repaired.push(unit)
+        }
+      }
+    }
+    *nodes = repaired;
+  }
+
+  // check that a tour visits every node once, without forbidden edges, and satisfies every constraint
+  // returns the first violation found
+  pub fn check(&self, labels: &Labels, distance_matrix: &Matrix, nodes: &[usize]) -> Result<(), String> {
+    match self.violations(labels, distance_matrix, nodes).into_iter().next() {
+      Some(violation) => Err(violation),
+      None => Ok(())
+    }
+  }
+
+  // returns every violation of a tour : the nodes it doesn't visit exactly once, and if it is a permutation of the nodes,
+  // its forbidden edges, fixed edges it doesn't use and precedences it doesn't respect
+  pub fn violations(&self, labels: &Labels, distance_matrix: &Matrix, nodes: &[usize]) -> Vec<String> {
+    let mut violations = Vec::new();
+    if nodes.len() != labels.len() {
+      violations.push(format!("the tour visits {} nodes instead of {}", nodes.len(), labels.len()));
+    }
+    let mut positions: Vec<Option<usize>> = vec![None; labels.len()];
+    for (position, &node) in nodes.iter().enumerate() {
+      if node >= labels.len() {
+        violations.push(format!("the tour visits the unknown node {}", node));
+      } else if positions[node].is_some() {
+        violations.push(format!("{} is visited twice", labels[node]));
+      } else {
+        positions[node] = Some(position);
+      }
+    }
+    if !violations.is_empty() {
+      return violations;
+    }
+    let positions: Vec<usize> = positions.into_iter().map(|position| position.expect("Unable to find the position of a node")).collect();
+
+    for edge in nodes.windows(2).filter(|edge| !distance_matrix.get(edge[0], edge[1]).is_finite()) {
+      violations.push(format!("the edge from {} to {} is forbidden", labels[edge[0]], labels[edge[1]]));
+    }
+    for node in 0..labels.len() {
+      if let Some(successor) = self.fixed_successors[node].filter(|&successor| positions[successor] != positions[node] + 1) {
+        violations.push(format!("{} should be followed by {}", labels[node], labels[successor]));
+      }
+    }
+    for &(before, after) in self.precedences.iter().filter(|&&(before, after)| positions[before] > positions[after]) {
+      violations.push(format!("{} should be visited before {}", labels[before], labels[after]));
+    }
+    violations
+  }
+}
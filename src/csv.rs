@@ -0,0 +1,86 @@
+use crate::dataset::Labels;
+
+// define the content of a file in the CSV format : locations or a distance matrix
+pub struct CsvFile {
+  pub labels: Labels,
+  pub locations: Vec<(f64, f64)>,
+  // only filled for distance matrices, an empty cell being a forbidden edge
+  pub distance_matrix: Vec<Vec<Option<f64>>>
+}
+
+// split a line into its fields, which can be quoted to contain the delimiter (a quote being escaped by doubling it)
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+  let mut fields = Vec::new();
+  let mut field = String::new();
+  let mut quoted = false;
+  let mut characters = line.chars().peekable();
+  while let Some(character) = characters.next() {
+    match character {
+      '"' if quoted && characters.peek() == Some(&'"') => {
+        field.push('"');
+        characters.next();
+      },
+      '"' => quoted = !quoted,
+      _ if character == delimiter && !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+      _ => field.push(character)
+    }
+  }
+  fields.push(field.trim().to_string());
+  fields
+}
+
+// returns the number of a field, panicking with the line it is on if it isn't a number
+fn parse_number(field: &str, line: usize) -> f64 {
+  field.parse::<f64>().unwrap_or_else(|_| panic!("Unable to parse the number {} on line {} of the dataset file", field, line))
+}
+
+// read a CSV file made of locations or of a distance matrix
+// locations are given by lines of label, x and y, with an optional header line
+// a distance matrix starts with a header line of labels (after an optional corner cell), followed by a line per node : its label and its distances to every node
+pub fn parse(content: &str, delimiter: char) -> CsvFile {
+  // the line numbers are kept to report errors
+  let records: Vec<(usize, Vec<String>)> = content.lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty())
+    .map(|(index, line)| (index + 1, split_fields(line, delimiter)))
+    .collect();
+  if records.is_empty() {
+    panic!("The dataset file is empty");
+  }
+
+  // a distance matrix is recognized by its header line, which repeats the labels of the other lines (after an optional corner cell),
+  // so that numeric labels aren't taken for coordinates
+  let (_, first) = &records[0];
+  let rows = &records[1..];
+  let row_labels: Labels = rows.iter().map(|(_, fields)| fields[0].clone()).collect();
+  let header_labels = match first.len() == rows.len() + 1 {
+    true => &first[1..],
+    false => &first[..]
+  };
+  if !rows.is_empty() && header_labels == row_labels.as_slice() {
+    let distance_matrix = rows.iter()
+      .map(|(line, fields)| {
+        if fields.len() != rows.len() + 1 {
+          panic!("Line {} of the dataset file should contain a label and {} distances, found {} fields", line, rows.len(), fields.len());
+        }
+        fields[1..].iter().map(|field| if field.is_empty() { None } else { Some(parse_number(field, *line)) }).collect()
+      })
+      .collect();
+    return CsvFile { labels: row_labels, locations: Vec::new(), distance_matrix };
+  }
+
+  // the header line of locations has fields that aren't numbers after its first one
+  let has_header = first.iter().skip(1).any(|field| field.parse::<f64>().is_err());
+  let data = if has_header { rows } else { &records[..] };
+  let data_labels: Labels = data.iter().map(|(_, fields)| fields[0].clone()).collect();
+
+  let locations = data.iter()
+    .map(|(line, fields)| {
+      if fields.len() != 3 {
+        panic!("Line {} of the dataset file should contain a label, x and y, found {} fields (distance matrices need a header line with the labels of the nodes)", line, fields.len());
+      }
+      (parse_number(&fields[1], *line), parse_number(&fields[2], *line))
+    })
+    .collect();
+  CsvFile { labels: data_labels, locations, distance_matrix: Vec::new() }
+}
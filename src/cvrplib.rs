@@ -0,0 +1,176 @@
+use crate::dataset::{LabelIndex,Labels};
+use crate::storage::Rounding;
+
+// define the content of a file in the TSPLIB / CVRPLIB format (.vrp or .tsp)
+pub struct VrpFile {
+  pub labels: Labels,
+  pub locations: Vec<(f64, f64)>,
+  // only filled for explicit distances
+  pub distance_matrix: Vec<Vec<f64>>,
+  // rounding of the distances between locations
+  pub rounding: Rounding,
+  pub demands: Vec<f64>,
+  pub capacity: Option<f64>,
+  pub depot: Option<String>
+}
+
+// returns the rounding of the distances of an edge weight type
+fn edge_weight_rounding(edge_weight_type: &str) -> Rounding {
+  match edge_weight_type {
+    "EUC_2D" => Rounding::Nint,
+    "CEIL_2D" => Rounding::Ceil,
+    "ATT" => Rounding::Att,
+    "EXACT_2D" | "EXPLICIT" => Rounding::None,
+    _ => panic!("The edge weight type {} isn't supported (EUC_2D, CEIL_2D, ATT, EXACT_2D or EXPLICIT)", edge_weight_type)
+  }
+}
+
+// build the full distance matrix from the values of the EDGE_WEIGHT_SECTION
+fn explicit_matrix(size: usize, edge_weight_format: &str, weights: &[f64]) -> Vec<Vec<f64>> {
+  let mut matrix = vec![vec![0.0; size]; size];
+
+  // list the cells given by the format, in order
+  let cells: Vec<(usize, usize)> = match edge_weight_format {
+    "FULL_MATRIX" => (0..size).flat_map(|i| (0..size).map(move |j| (i, j))).collect(),
+    "LOWER_ROW" => (0..size).flat_map(|i| (0..i).map(move |j| (i, j))).collect(),
+    "LOWER_DIAG_ROW" => (0..size).flat_map(|i| (0..=i).map(move |j| (i, j))).collect(),
+    "UPPER_ROW" => (0..size).flat_map(|i| (i + 1..size).map(move |j| (i, j))).collect(),
+    "UPPER_DIAG_ROW" => (0..size).flat_map(|i| (i..size).map(move |j| (i, j))).collect(),
+    _ => panic!("The edge weight format {} isn't supported (FULL_MATRIX, LOWER_ROW, LOWER_DIAG_ROW, UPPER_ROW or UPPER_DIAG_ROW)", edge_weight_format)
+  };
+  if cells.len() != weights.len() {
+    panic!("The EDGE_WEIGHT_SECTION should contain {} values for a {} matrix of {} nodes, found {}", cells.len(), edge_weight_format, size, weights.len());
+  }
+
+  // triangular formats describe symmetric matrices
+  for (&(i, j), &weight) in cells.iter().zip(weights.iter()) {
+    matrix[i][j] = weight;
+    if edge_weight_format != "FULL_MATRIX" {
+      matrix[j][i] = weight;
+    }
+  }
+  matrix
+}
+
+// parse a file in the TSPLIB / CVRPLIB format
+pub fn parse(content: &str) -> VrpFile {
+  let mut dimension: Option<usize> = None;
+  let mut edge_weight_type = String::from("EUC_2D");
+  let mut edge_weight_format = String::from("FULL_MATRIX");
+  let mut capacity: Option<f64> = None;
+  let mut coordinates: Vec<(String, f64, f64)> = Vec::new();
+  let mut demands: Vec<(String, f64)> = Vec::new();
+  let mut weights: Vec<f64> = Vec::new();
+  let mut depots: Vec<String> = Vec::new();
+
+  let parse_number = |token: &str| token.parse::<f64>().unwrap_or_else(|_| panic!("Unable to parse the number {} of the dataset file", token));
+
+  // sections are made of the lines following their name, until another keyword
+  let mut section = String::new();
+  for line in content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let is_data = tokens[0].parse::<f64>().is_ok();
+
+    if !is_data {
+      section.clear();
+      match line.split_once(':') {
+        Some((key, value)) => {
+          let value = value.trim();
+          match key.trim() {
+            "DIMENSION" => dimension = Some(value.parse().unwrap_or_else(|_| panic!("Unable to parse the dimension {}", value))),
+            "EDGE_WEIGHT_TYPE" => edge_weight_type = value.to_string(),
+            "EDGE_WEIGHT_FORMAT" => edge_weight_format = value.to_string(),
+            "CAPACITY" => capacity = Some(parse_number(value)),
+            _ => {}
+          }
+        },
+        None if line == "EOF" => break,
+        None => section = line.to_string()
+      }
+      continue;
+    }
+
+    match section.as_str() {
+      "NODE_COORD_SECTION" => {
+        if tokens.len() < 3 {
+          panic!("The line {} of the NODE_COORD_SECTION should contain a node and its coordinates", line);
+        }
+        coordinates.push((tokens[0].to_string(), parse_number(tokens[1]), parse_number(tokens[2])));
+      },
+      "DEMAND_SECTION" => {
+        if tokens.len() < 2 {
+          panic!("The line {} of the DEMAND_SECTION should contain a node and its demand", line);
+        }
+        demands.push((tokens[0].to_string(), parse_number(tokens[1])));
+      },
+      "EDGE_WEIGHT_SECTION" => weights.extend(tokens.iter().map(|&token| parse_number(token))),
+      "DEPOT_SECTION" => depots.extend(tokens.iter().filter(|&&token| token != "-1").map(|&token| token.to_string())),
+      _ => {}
+    }
+  }
+
+  let size = dimension.expect("The dataset file should give its DIMENSION");
+  if depots.len() > 1 {
+    panic!("Only one depot is supported, found {}", depots.len());
+  }
+
+  // nodes are labelled by their number in the file
+  let labels: Labels = if coordinates.is_empty() {
+    (1..=size).map(|node| node.to_string()).collect()
+  } else {
+    coordinates.iter().map(|(label, _, _)| label.clone()).collect()
+  };
+  if labels.len() != size {
+    panic!("The dataset file should contain {} nodes, found {}", size, labels.len());
+  }
+
+  // demands are given by node, in any order
+  let demands: Vec<f64> = if demands.is_empty() {
+    Vec::new()
+  } else {
+    let label_index = LabelIndex::new(&labels);
+    let mut node_demands: Vec<Option<f64>> = vec![None; size];
+    for (label, demand) in demands {
+      let node = label_index.get(&label).unwrap_or_else(|| panic!("Unknown node {} in the DEMAND_SECTION", label));
+      node_demands[node] = Some(demand);
+    }
+    node_demands.into_iter().enumerate()
+      .map(|(node, demand)| demand.unwrap_or_else(|| panic!("The demand of node {} is missing", labels[node])))
+      .collect()
+  };
+
+  let rounding = edge_weight_rounding(&edge_weight_type);
+  let (locations, distance_matrix) = if edge_weight_type == "EXPLICIT" {
+    (Vec::new(), explicit_matrix(size, &edge_weight_format, &weights))
+  } else {
+    (coordinates.iter().map(|&(_, x, y)| (x, y)).collect(), Vec::new())
+  };
+
+  VrpFile { labels, locations, distance_matrix, rounding, demands, capacity, depot: depots.pop() }
+}
+
+// returns the labels of the nodes of a tour in the TSPLIB format (.tour), given by their number in the dataset file
+// the TOUR_SECTION ends with -1 or at the end of the file, and only the first tour is read
+pub fn parse_tour(content: &str) -> Labels {
+  let mut labels = Vec::new();
+  let mut in_tour_section = false;
+  for token in content.lines().map(str::trim).filter(|line| !line.is_empty()).flat_map(|line| {
+    let is_data = line.split_whitespace().next().is_some_and(|token| token.parse::<i64>().is_ok());
+    if is_data { line.split_whitespace().collect() } else { vec![line] }
+  }) {
+    if !in_tour_section {
+      in_tour_section = token == "TOUR_SECTION";
+      continue;
+    }
+    match token.parse::<i64>() {
+      Ok(-1) => break,
+      Ok(node) if node > 0 => labels.push(node.to_string()),
+      Ok(node) => panic!("Unable to read the node {} of the tour file", node),
+      Err(_) => break
+    }
+  }
+  if !in_tour_section {
+    panic!("The tour file should contain a TOUR_SECTION");
+  }
+  labels
+}
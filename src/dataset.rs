@@ -1,88 +1,389 @@
 use std::path::Path;
 use std::fs::read_to_string;
-use std::collections::HashSet;
+use std::collections::{BTreeMap,HashMap,HashSet};
+use rand::thread_rng;
 use serde::Deserialize;
+use crate::candidates::{CandidateSet,alpha_nearness,delaunay_neighbors,quadrant_neighbors};
+use crate::clusters::Clusters;
+use crate::constraints::Constraints;
+use crate::csv;
+use crate::cvrplib;
+use crate::feasibility::AllowedEdges;
+use crate::orienteering::Orienteering;
+use crate::penalties::NodeCosts;
+use crate::routes::{Fleet,RoutesObjective};
+use crate::schedule::{TimeWindows,TimeWindowsMode,DEFAULT_LATENESS_PENALTY};
+use crate::spatial::KdTree;
+use crate::storage::{CacheMode,FlatMatrix,Layout,NeighborsCache,Precision,Rounding,FULL_CACHE_MAX_SIZE};
 use crate::utils::{get_max_display_width,get_max_display_width_thousands_2d,ThousandsDisplayPolicy};
 
 // alias often reused types
 pub type Labels = Vec<String>;
 pub type NeighborsMatrix = Vec<Vec<usize>>;
 
+// map each label to its node, to find nodes by their label without scanning the labels
+pub struct LabelIndex {
+  nodes: HashMap<String, usize>
+}
+
+impl LabelIndex {
+  pub fn new(labels: &Labels) -> Self {
+    Self { nodes: labels.iter().enumerate().map(|(node, label)| (label.clone(), node)).collect() }
+  }
+
+  // returns the node of a label, or None if no node has this label
+  pub fn get(&self, label: &str) -> Option<usize> {
+    self.nodes.get(label).copied()
+  }
+
+  // returns the node of a label, or an error naming the unknown label
+  pub fn node(&self, label: &str) -> Result<usize, String> {
+    self.get(label).ok_or_else(|| format!("unknown label {}", label))
+  }
+}
+
+// default number of candidate neighbors kept for each node
+pub const DEFAULT_CANDIDATES_COUNT: usize = 10;
+
+// integer distances can't be infinite, so forbidden edges are stored with this value
+pub const FORBIDDEN_INTEGER_DISTANCE: i32 = i32::MAX;
+
 pub enum Matrix {
-  Distances(Vec<Vec<f64>>),
-  Locations(Vec<(f64, f64)>)
+  Distances(FlatMatrix<f64>),
+  CompactDistances(FlatMatrix<f32>),
+  IntegerDistances(FlatMatrix<i32>),
+  Locations(Vec<(f64, f64)>, Rounding),
+  CachedLocations(NeighborsCache)
 }
 
 impl Matrix {
-  pub fn get(&self, node1: usize, node2: usize) -> f64 {
-    match self {
-      Self::Distances(matrix) => matrix[node1][node2],
-      Self::Locations(matrix) => {
-        let (p1_x, p1_y) = matrix[node1];
-        let (p2_x, p2_y) = matrix[node2];
+  // create a distance matrix by computing each distance, stored with the given precision and layout
+  // forbidden edges have an infinite distance, and integer matrices reject fractional distances instead of rounding them
+  pub fn from_fn<F: Fn(usize, usize) -> f64>(size: usize, symmetric: bool, precision: Precision, layout: Layout, distance: F) -> Self {
+    let triangular = match layout {
+      Layout::Auto => symmetric,
+      Layout::Full => false,
+      Layout::Triangular => {
+        if !symmetric {
+          panic!("The triangular layout can only be used with symmetric distances");
+        }
+        true
+      }
+    };
+
+    match precision {
+      Precision::F64 => Self::Distances(FlatMatrix::from_fn(size, triangular, distance)),
+      Precision::F32 => Self::CompactDistances(FlatMatrix::from_fn(size, triangular, |i, j| distance(i, j) as f32)),
+      Precision::Integer => Self::IntegerDistances(FlatMatrix::from_fn(size, triangular, |i, j| {
+        let value = distance(i, j);
+        if value == f64::INFINITY {
+          return FORBIDDEN_INTEGER_DISTANCE;
+        }
+        if !value.is_finite() || value.abs() >= FORBIDDEN_INTEGER_DISTANCE as f64 {
+          panic!("The distance between nodes {} and {} can't be stored as an integer, found {}", i, j, value);
+        }
+        if value.fract() != 0.0 {
+          panic!("The distance between nodes {} and {} isn't an integer, found {} : use a rounding to store fractional distances as integers", i, j, value);
+        }
+        value as i32
+      }))
+    }
+  }
 
-        let delta_x = p2_x - p1_x;
-        let delta_y = p2_y - p1_y;
+  // create a distance matrix from its rows
+  // rounded distances are always stored as integers
+  pub fn from_rows(rows: &[Vec<f64>], precision: Precision, layout: Layout, rounding: Rounding) -> Self {
+    // every row should have one distance per node
+    if rows.is_empty() {
+      panic!("The distance matrix should not be empty");
+    }
+    if let Some((index, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != rows.len()) {
+      panic!("Your distances matrix isn't a square : row {} has {} distances, but there are {} rows", index, row.len(), rows.len());
+    }
 
-        (delta_x*delta_x + delta_y*delta_y).sqrt()
-      }
+    let symmetric = (0..rows.len()).all(|i| (0..i).all(|j| rows[i][j] == rows[j][i]));
+    if rounding == Rounding::None {
+      Self::from_fn(rows.len(), symmetric, precision, layout, |i, j| rows[i][j])
+    } else {
+      Self::from_fn(rows.len(), symmetric, Precision::Integer, layout, |i, j| rounding.round(rows[i][j]))
+    }
+  }
+
+  pub fn get(&self, node1: usize, node2: usize) -> f64 {
+    match self {
+      Self::Distances(matrix) => matrix.get(node1, node2),
+      Self::CompactDistances(matrix) => matrix.get(node1, node2),
+      Self::IntegerDistances(matrix) => {
+        let distance = matrix.get(node1, node2);
+        if distance == FORBIDDEN_INTEGER_DISTANCE as f64 { f64::INFINITY } else { distance }
+      },
+      Self::Locations(matrix, rounding) => rounding.distance(matrix[node1], matrix[node2]),
+      Self::CachedLocations(cache) => cache.get(node1, node2)
     }
   }
 
   pub fn len(&self) -> usize {
     match self {
       Self::Distances(matrix) => matrix.len(),
-      Self::Locations(matrix) => matrix.len()
+      Self::CompactDistances(matrix) => matrix.len(),
+      Self::IntegerDistances(matrix) => matrix.len(),
+      Self::Locations(matrix, _) => matrix.len(),
+      Self::CachedLocations(cache) => cache.locations().len()
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn is_symmetric(&self) -> bool {
+    match self {
+      Self::Distances(matrix) => matrix.is_symmetric(),
+      Self::CompactDistances(matrix) => matrix.is_symmetric(),
+      Self::IntegerDistances(matrix) => matrix.is_symmetric(),
+      Self::Locations(..) | Self::CachedLocations(_) => true
     }
   }
 
-  pub fn is_square(&self) -> bool {
+  // whether every distance is an integer, in which case lengths are summed as integers
+  pub fn is_integer(&self) -> bool {
     match self {
-      Self::Distances(matrix) => matrix.len() == matrix[0].len(),
-      Self::Locations(_) => true
+      Self::IntegerDistances(_) => true,
+      Self::Locations(_, rounding) => *rounding != Rounding::None,
+      Self::CachedLocations(cache) => cache.rounding() != Rounding::None,
+      _ => false
     }
   }
 
+  // returns the longest distance, forbidden edges excluded
   pub fn max(&self) -> f64 {
-    let mut current_max: Option<f64> = None;
-
-    for i in 0..self.len() {
-      for j in 0..self.len() {
-        match current_max {
-          None => { current_max = Some(self.get(i, j)); },
-          Some(max) => {
-            let d: f64 = self.get(i, j);
-            if max < d {
-              current_max = Some(d);
-            }
-          }
-        }
-      }
+    match self {
+      Self::Distances(matrix) => matrix.max(f64::INFINITY),
+      Self::CompactDistances(matrix) => matrix.max(f32::INFINITY),
+      Self::IntegerDistances(matrix) => matrix.max(FORBIDDEN_INTEGER_DISTANCE),
+      // the diagonal of the bounding box is an upper bound of the distances between locations
+      // and avoids comparing every pair of locations on big datasets
+      Self::Locations(matrix, rounding) => bounding_box_diagonal(matrix, *rounding),
+      Self::CachedLocations(cache) => bounding_box_diagonal(cache.locations(), cache.rounding())
     }
+  }
+}
 
-    current_max.unwrap()
+// compute the length of the diagonal of the box containing every location
+fn bounding_box_diagonal(locations: &[(f64, f64)], rounding: Rounding) -> f64 {
+  let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+  for &(x, y) in locations.iter() {
+    min_x = min_x.min(x);
+    min_y = min_y.min(y);
+    max_x = max_x.max(x);
+    max_y = max_y.max(y);
   }
+  rounding.distance((min_x, min_y), (max_x, max_y))
 }
 
 // define a struct to represent a loaded but unverified dataset
 // this struct is used to load the dataset from a file using deserialization
+// a null distance is a forbidden edge
 #[derive(Deserialize)]
 struct UnsafeDataset {
   labels: Labels,
-  distance_matrix: Vec<Vec<f64>>,
-  locations: Vec<(f64, f64)>
+  distance_matrix: Vec<Vec<Option<f64>>>,
+  locations: Vec<(f64, f64)>,
+  // pairs of labels that must be visited one right after the other
+  #[serde(default)]
+  fixed_edges: Vec<(String, String)>,
+  // lists of labels that must be visited consecutively, in order
+  #[serde(default)]
+  fixed_segments: Vec<Vec<String>>,
+  // pairs of labels where the first one must be visited before the second one
+  #[serde(default)]
+  precedences: Vec<(String, String)>,
+  // [opening, closing] time of each node, or null
+  #[serde(default)]
+  time_windows: Vec<Option<(f64, f64)>>,
+  // time spent at each node
+  #[serde(default)]
+  service_times: Vec<f64>,
+  // label of the node the vehicles start from
+  #[serde(default)]
+  depot: Option<String>,
+  // capacity of each vehicle, and quantity delivered to each node
+  #[serde(default)]
+  capacity: Option<f64>,
+  #[serde(default)]
+  demands: Vec<f64>,
+  // prize collected by visiting each node, and maximum length of the tour
+  #[serde(default)]
+  prizes: Vec<f64>,
+  #[serde(default)]
+  budget: Option<f64>,
+  // lists of labels of which only one node is visited
+  #[serde(default)]
+  clusters: Vec<Vec<String>>,
+  // other costs of the edges, minimized together with the distances, by name
+  #[serde(default)]
+  cost_matrices: BTreeMap<String, Vec<Vec<f64>>>,
+  // cost of visiting each node, and penalty of skipping each node (or null if it must be visited)
+  #[serde(default)]
+  node_costs: Vec<f64>,
+  #[serde(default)]
+  skip_penalties: Vec<Option<f64>>
+}
+
+// define the variants of the problem a dataset can describe, each one being optional
+// the default variants describe a plain traveling salesman problem
+#[derive(Default)]
+pub struct Variants {
+  pub time_windows: Option<TimeWindows>,
+  pub fleet: Option<Fleet>,
+  pub orienteering: Option<Orienteering>,
+  pub clusters: Option<Clusters>,
+  pub cost_matrices: Vec<(String, Matrix)>,
+  pub node_costs: Option<NodeCosts>
+}
+
+// define the features of a dataset, some of which can't be combined
+#[derive(Clone,Copy,PartialEq)]
+enum Feature {
+  Fleet,
+  Prizes,
+  Clusters,
+  NodeCosts,
+  CostMatrices,
+  ForbiddenEdges,
+  FixedEdges,
+  Precedences,
+  TimeWindows
+}
+
+impl Feature {
+  fn name(self) -> &'static str {
+    match self {
+      Feature::Fleet => "several salesmen or vehicles",
+      Feature::Prizes => "prizes",
+      Feature::Clusters => "clusters",
+      Feature::NodeCosts => "node costs",
+      Feature::CostMatrices => "several cost matrices",
+      Feature::ForbiddenEdges => "forbidden edges",
+      Feature::FixedEdges => "fixed edges",
+      Feature::Precedences => "precedences",
+      Feature::TimeWindows => "time windows"
+    }
+  }
+}
+
+// the pairs of features the solver can't combine, each pair being stated once
+// the routes of several vehicles are only split on complete graphs without constraints,
+// and the variants skipping or choosing the visited nodes are only supported on their own
+const INCOMPATIBLE_FEATURES: [(Feature, Feature); 24] = [
+  (Feature::Fleet, Feature::ForbiddenEdges), (Feature::Fleet, Feature::FixedEdges), (Feature::Fleet, Feature::Precedences), (Feature::Fleet, Feature::TimeWindows),
+  (Feature::Prizes, Feature::Fleet), (Feature::Prizes, Feature::ForbiddenEdges), (Feature::Prizes, Feature::FixedEdges), (Feature::Prizes, Feature::Precedences), (Feature::Prizes, Feature::TimeWindows),
+  (Feature::Clusters, Feature::Fleet), (Feature::Clusters, Feature::Prizes), (Feature::Clusters, Feature::ForbiddenEdges), (Feature::Clusters, Feature::FixedEdges), (Feature::Clusters, Feature::Precedences), (Feature::Clusters, Feature::TimeWindows),
+  (Feature::NodeCosts, Feature::Fleet), (Feature::NodeCosts, Feature::Prizes), (Feature::NodeCosts, Feature::Clusters), (Feature::NodeCosts, Feature::ForbiddenEdges), (Feature::NodeCosts, Feature::FixedEdges), (Feature::NodeCosts, Feature::Precedences), (Feature::NodeCosts, Feature::TimeWindows),
+  (Feature::CostMatrices, Feature::Fleet), (Feature::CostMatrices, Feature::Prizes)
+];
+
+// define the options used to build a dataset
+pub struct DatasetOptions {
+  // number of candidate neighbors kept for each node, used by crossover and mutation
+  pub candidates_count: usize,
+  // how the candidate neighbors are chosen
+  pub candidate_set: CandidateSet,
+  // how the distance matrices are stored, the precision of locations only being used when all their distances are cached
+  pub precision: Precision,
+  pub layout: Layout,
+  // how the distances between locations are cached
+  pub cache: CacheMode,
+  // how the distances are rounded to integers
+  pub rounding: Rounding,
+  // how the lateness of the tours is handled, with the cost of each time unit of lateness
+  pub time_windows_mode: TimeWindowsMode,
+  pub lateness_penalty: f64,
+  // number of salesmen starting from the depot (the depot of the dataset or the first node if not given), and what their routes minimize
+  pub salesmen: usize,
+  pub depot: Option<String>,
+  pub routes_objective: RoutesObjective,
+  // delimiter of the fields of CSV dataset files
+  pub csv_delimiter: char
+}
+
+impl Default for DatasetOptions {
+  fn default() -> Self {
+    Self {
+      candidates_count: DEFAULT_CANDIDATES_COUNT,
+      candidate_set: CandidateSet::Nearest,
+      precision: Precision::F64,
+      layout: Layout::Auto,
+      cache: CacheMode::Auto,
+      rounding: Rounding::None,
+      time_windows_mode: TimeWindowsMode::Penalty,
+      lateness_penalty: DEFAULT_LATENESS_PENALTY,
+      salesmen: 1,
+      depot: None,
+      routes_objective: RoutesObjective::MinSum,
+      csv_delimiter: ','
+    }
+  }
+}
+
+impl UnsafeDataset {
+  // returns an unsafe dataset made of distances or locations only, for the file formats without the other fields
+  fn new(labels: Labels, distance_matrix: Vec<Vec<Option<f64>>>, locations: Vec<(f64, f64)>) -> Self {
+    Self {
+      labels,
+      distance_matrix,
+      locations,
+      fixed_edges: Vec::new(),
+      fixed_segments: Vec::new(),
+      precedences: Vec::new(),
+      time_windows: Vec::new(),
+      service_times: Vec::new(),
+      depot: None,
+      capacity: None,
+      demands: Vec::new(),
+      prizes: Vec::new(),
+      budget: None,
+      clusters: Vec::new(),
+      cost_matrices: BTreeMap::new(),
+      node_costs: Vec::new(),
+      skip_penalties: Vec::new()
+    }
+  }
 }
 
 // define the structure of the dataset
 pub struct Dataset {
   pub size: usize,
-  pub labels: Labels,
+  // only read through their accessors, the index having to match the labels
+  labels: Labels,
+  label_index: LabelIndex,
   pub distance_matrix: Matrix,
+  // rounding applied to the distances, kept to compute the distances of the nodes inserted later
+  pub rounding: Rounding,
 
   pub longest_path_length: f64,
   pub nodes_neighbors: NeighborsMatrix,
+  // only built for datasets made of locations
+  pub spatial_index: Option<KdTree>,
+  // only built for datasets with forbidden edges
+  pub allowed_edges: Option<AllowedEdges>,
+  pub constraints: Constraints,
+  // only built for datasets with time windows or service times
+  pub time_windows: Option<TimeWindows>,
+  // only built with several salesmen or vehicles
+  pub fleet: Option<Fleet>,
+  // only built for datasets with prizes
+  pub orienteering: Option<Orienteering>,
+  // only built for datasets with clusters
+  pub clusters: Option<Clusters>,
+  // other costs minimized together with the length, only given by datasets with several cost matrices
+  pub cost_matrices: Vec<(String, Matrix)>,
+  // only built for datasets with node costs or skip penalties
+  pub node_costs: Option<NodeCosts>,
 
   pub longest_label_display_width: usize,
+  // only read by the (commented out) table display of the dataset
+  #[allow(dead_code)]
   pub longest_distance_display_width: usize,
   pub longest_path_display_width: usize,
 }
@@ -108,23 +409,62 @@ impl Dataset {
     if labels_count != distance_matrix.len() {
       panic!("The number of labels should be the same as the number of nodes : your distances matrix isn't a square");
     }
-    if !distance_matrix.is_square() {
-      panic!("The number of labels should be the same as the number of nodes : your distances matrix isn't a square");
+
+    // verify the content of the matrix
+    match distance_matrix {
+      // locations should have finite coordinates, distances between them are then always valid
+      Matrix::Locations(locations, _) => {
+        for (node, &(x, y)) in locations.iter().enumerate() {
+          if !x.is_finite() || !y.is_finite() {
+            panic!("The location of {} (index {}) should have finite coordinates, found ({}, {})", labels[node], node, x, y);
+          }
+        }
+      },
+      // distances should be positive (or infinite for forbidden edges), and 0 from a node to itself
+      _ => {
+        for row in 0..labels_count {
+          for column in 0..labels_count {
+            let distance = distance_matrix.get(row, column);
+            if distance.is_nan() {
+              panic!("The distance from {} to {} (row {}, column {}) should be a number, found {}", labels[row], labels[column], row, column, distance);
+            }
+            if distance < 0.0 {
+              panic!("The distance from {} to {} (row {}, column {}) should not be negative, found {}", labels[row], labels[column], row, column, distance);
+            }
+            if row == column && distance != 0.0 {
+              panic!("The distance from {} to itself (row {}, column {}) should be 0, found {}", labels[row], row, column, distance);
+            }
+          }
+        }
+      }
     }
   }
 
-  // find neighbors for each node
-  fn find_neighbors(distance_matrix: &Matrix) -> NeighborsMatrix {
+  // find the nearest neighbors of each node (the node itself excluded)
+  // only the candidates are kept, so the memory used is O(n * candidates_count)
+  fn find_neighbors(distance_matrix: &Matrix, spatial_index: &Option<KdTree>, candidates_count: usize) -> NeighborsMatrix {
+    // query the spatial index if there is one, in O(n * log n) instead of O(n^2)
+    if let (Some(spatial_index), Matrix::Locations(locations, _)) = (spatial_index, distance_matrix) {
+      return (0..locations.len())
+        .map(|node| spatial_index.k_nearest(locations[node], candidates_count, |neighbor| neighbor != node))
+        .collect();
+    }
+
     // create a vector that will contain the neighbors for each node
-    let mut node_neighbors: NeighborsMatrix = Vec::new();
+    let mut node_neighbors: NeighborsMatrix = Vec::with_capacity(distance_matrix.len());
 
     // for each node in the dataset ...
     for node in 0..distance_matrix.len() {
       // create a vector that will contain the nearest neighbors for the current node
-      let mut neighbors: Vec<usize> = (0..distance_matrix.len()).collect();
+      let mut neighbors: Vec<usize> = (0..distance_matrix.len()).filter(|&neighbor| neighbor != node).collect();
+      let compare = |n1: &usize, n2: &usize| distance_matrix.get(node, *n1).partial_cmp(&distance_matrix.get(node, *n2)).expect("Error while computing neighbors");
 
-      // sort the nodes by distance to the current node
-      neighbors.sort_by(|&n1, &n2| distance_matrix.get(node, n1).partial_cmp(&distance_matrix.get(node, n2)).expect("Error while computing neighbors"));
+      // move the nearest nodes to the front without sorting the others, then sort them by distance
+      if candidates_count < neighbors.len() {
+        neighbors.select_nth_unstable_by(candidates_count - 1, compare);
+        neighbors.truncate(candidates_count);
+      }
+      neighbors.sort_by(compare);
 
       // append to the vector of nearest neighbors
       node_neighbors.push(neighbors);
@@ -134,6 +474,29 @@ impl Dataset {
     node_neighbors
   }
 
+  // cache the distances between locations, depending on the cache mode and the size of the dataset
+  fn cache_distances(distance_matrix: Matrix, nodes_neighbors: &NeighborsMatrix, options: &DatasetOptions) -> Matrix {
+    let (locations, rounding) = match distance_matrix {
+      Matrix::Locations(locations, rounding) => (locations, rounding),
+      matrix => return matrix
+    };
+
+    // the full matrix is only worth it for small datasets, big ones only cache the distances to the neighbors
+    let cache = match options.cache {
+      CacheMode::Auto => if locations.len() <= FULL_CACHE_MAX_SIZE { CacheMode::Full } else { CacheMode::Neighbors },
+      cache => cache
+    };
+
+    // rounded distances are always stored as integers
+    let precision = if rounding == Rounding::None { options.precision } else { Precision::Integer };
+
+    match cache {
+      CacheMode::Full => Matrix::from_fn(locations.len(), true, precision, options.layout, |i, j| rounding.distance(locations[i], locations[j])),
+      CacheMode::Neighbors => Matrix::CachedLocations(NeighborsCache::new(locations, rounding, nodes_neighbors)),
+      _ => Matrix::Locations(locations, rounding)
+    }
+  }
+
   // find the longest possible path length (may actually not be a valid path !)
   fn update_longest_path_length(&mut self) {
     // find the longest value inside the distance matrix
@@ -147,27 +510,109 @@ impl Dataset {
     self.longest_path_display_width = longest_path_length.thousands().len();
   }
 
+  // returns a new dataset of a plain traveling salesman problem, without constraints nor variants
+  pub fn from_matrix(labels: Labels, distance_matrix: Matrix, options: &DatasetOptions) -> Self {
+    let constraints = Constraints::none(&labels);
+    Self::new(labels, distance_matrix, constraints, Variants::default(), options)
+  }
+
   // function that allows to create a new dataset object
-  pub fn new(labels: Labels, distance_matrix: Matrix) -> Self {
-    // verify the dataset
+  pub fn new(labels: Labels, distance_matrix: Matrix, constraints: Constraints, variants: Variants, options: &DatasetOptions) -> Self {
+    // verify the dataset, and that a feasible tour exists if some edges are forbidden
     Self::verify(&labels, &distance_matrix);
+    constraints.verify(&labels, &distance_matrix);
+    let allowed_edges = AllowedEdges::new(&labels, &distance_matrix, &constraints, &mut thread_rng());
 
     // compute column's widths
     let longest_label_display_width = get_max_display_width(&labels);
     let longest_distance_display_width = get_max_display_width_thousands_2d(&distance_matrix);
 
-    // compute nearest neighbors
-    let nodes_neighbors = Self::find_neighbors(&distance_matrix);
+    // build the spatial index of the locations and compute nearest neighbors
+    let spatial_index = match &distance_matrix {
+      Matrix::Locations(locations, _) => Some(KdTree::new(locations)),
+      _ => None
+    };
+
+    // there can't be more candidates than other nodes
+    let candidates_count = options.candidates_count.clamp(1, labels.len() - 1);
+    let nodes_neighbors = match (options.candidate_set, &distance_matrix, &spatial_index) {
+      (CandidateSet::Nearest, _, _) => Self::find_neighbors(&distance_matrix, &spatial_index, candidates_count),
+      (CandidateSet::Alpha, _, _) => alpha_nearness(&distance_matrix, candidates_count),
+      (CandidateSet::Quadrant, Matrix::Locations(locations, _), Some(spatial_index)) => quadrant_neighbors(locations, spatial_index, candidates_count),
+      (CandidateSet::Delaunay, Matrix::Locations(locations, _), Some(spatial_index)) => delaunay_neighbors(locations, spatial_index, candidates_count),
+      _ => panic!("The quadrant and Delaunay candidate sets can only be used with datasets made of locations")
+    };
+
+    // forbidden edges can't be candidates
+    let nodes_neighbors = if allowed_edges.is_some() {
+      nodes_neighbors.into_iter()
+        .enumerate()
+        .map(|(node, neighbors)| neighbors.into_iter().filter(|&neighbor| distance_matrix.get(node, neighbor).is_finite()).collect())
+        .collect()
+    } else {
+      nodes_neighbors
+    };
+
+    // reject the features that can't be combined
+    let Variants { time_windows, fleet, orienteering, clusters, cost_matrices, node_costs } = variants;
+    let features = [
+      (Feature::Fleet, fleet.is_some()),
+      (Feature::Prizes, orienteering.is_some()),
+      (Feature::Clusters, clusters.is_some()),
+      (Feature::NodeCosts, node_costs.is_some()),
+      (Feature::CostMatrices, !cost_matrices.is_empty()),
+      (Feature::ForbiddenEdges, allowed_edges.is_some()),
+      (Feature::FixedEdges, !constraints.segments.is_empty()),
+      (Feature::Precedences, !constraints.precedences.is_empty()),
+      (Feature::TimeWindows, time_windows.is_some())
+    ];
+    let enabled = |feature: Feature| features.iter().any(|&(other, used)| used && other == feature);
+    if let Some((feature1, feature2)) = INCOMPATIBLE_FEATURES.iter().find(|&&(feature1, feature2)| enabled(feature1) && enabled(feature2)) {
+      let name = feature1.name();
+      panic!("{}{} can't be used with {}", name[..1].to_uppercase(), &name[1..], feature2.name());
+    }
+    for (name, cost_matrix) in cost_matrices.iter() {
+      if cost_matrix.len() != labels.len() {
+        panic!("The cost matrix {} should have one row per node, found {} rows for {} nodes", name, cost_matrix.len(), labels.len());
+      }
+      for (row, column) in (0..labels.len()).flat_map(|row| (0..labels.len()).map(move |column| (row, column))) {
+        let cost = cost_matrix.get(row, column);
+        if !cost.is_finite() || cost < 0.0 {
+          panic!("The cost {} from {} to {} should be a positive number, found {}", name, labels[row], labels[column], cost);
+        }
+      }
+    }
+
+    // the rounding of distance matrices is applied when reading their rows, and set by the caller
+    let rounding = match &distance_matrix {
+      Matrix::Locations(_, rounding) => *rounding,
+      Matrix::CachedLocations(cache) => cache.rounding(),
+      _ => Rounding::None
+    };
+
+    // cache the distances once the neighbors are known
+    let distance_matrix = Self::cache_distances(distance_matrix, &nodes_neighbors, options);
 
     // create and return the object
     let mut dataset = Self {
       size: labels.len(),
+      label_index: LabelIndex::new(&labels),
       labels,
       distance_matrix,
+      rounding,
 
       longest_path_length: 0.0,
       nodes_neighbors,
-      
+      spatial_index,
+      allowed_edges,
+      constraints,
+      time_windows,
+      fleet,
+      orienteering,
+      clusters,
+      cost_matrices,
+      node_costs,
+
       longest_label_display_width,
       longest_distance_display_width,
       longest_path_display_width: 0
@@ -176,31 +621,160 @@ impl Dataset {
     dataset
   }
 
+  // whether the value minimized by the genetic algorithm differs from the length of the tours
+  pub fn has_objective(&self) -> bool {
+    self.time_windows.is_some() || self.fleet.is_some() || self.orienteering.is_some() || self.node_costs.is_some()
+  }
+
+  // returns the labels of the nodes, in the order of the nodes
+  pub fn labels(&self) -> &Labels {
+    &self.labels
+  }
+
+  // returns the index finding the node of each label
+  pub fn label_index(&self) -> &LabelIndex {
+    &self.label_index
+  }
+
+  // returns the node of a label, or an error if no node has this label
+  pub fn node(&self, label: &str) -> Result<usize, String> {
+    self.label_index.node(label)
+  }
+
+  // returns the distance from a node to another one, given by their labels
+  pub fn distance_between(&self, from: &str, to: &str) -> Result<f64, String> {
+    Ok(self.distance_matrix.get(self.node(from)?, self.node(to)?))
+  }
+
+  // returns the nodes of a tour given by labels, or an error on the first unknown label
+  pub fn nodes_of(&self, labels: &[String]) -> Result<Vec<usize>, String> {
+    labels.iter().map(|label| self.node(label)).collect()
+  }
+
+  // returns the labels of the nodes of a tour
+  pub fn labels_of(&self, nodes: &[usize]) -> Labels {
+    nodes.iter().map(|&node| self.labels[node].clone()).collect()
+  }
+
+  // returns the depot given in the options, or the one of the dataset, or the first node
+  fn find_depot(label_index: &LabelIndex, depot: Option<&String>, options: &DatasetOptions) -> usize {
+    match options.depot.as_ref().or(depot) {
+      Some(depot) => label_index.get(depot).unwrap_or_else(|| panic!("Unknown depot {}", depot)),
+      None => 0
+    }
+  }
+
+  // build the fleet of the dataset : several salesmen, or vehicles with a capacity if the nodes have demands
+  // returns None with a single salesman
+  fn build_fleet(labels: &Labels, depot: usize, capacity: Option<f64>, demands: Vec<f64>, options: &DatasetOptions) -> Option<Fleet> {
+    match (capacity, demands.is_empty()) {
+      (None, true) if options.salesmen > 1 => Some(Fleet::new(depot, options.salesmen, options.routes_objective, labels.len())),
+      (None, true) => None,
+      (Some(capacity), false) => {
+        if options.salesmen > 1 || options.routes_objective != RoutesObjective::MinSum {
+          panic!("The number of vehicles only depends on their capacity : the number of salesmen and the routes objective can't be given");
+        }
+        Some(Fleet::capacitated(labels, depot, capacity, demands))
+      },
+      (None, false) => panic!("The dataset gives the demands of the nodes, but not the capacity of the vehicles"),
+      (Some(_), true) => panic!("The dataset gives the capacity of the vehicles, but not the demands of the nodes")
+    }
+  }
+
+  // load a dataset in the TSPLIB / CVRPLIB format into an unsafe dataset
+  // the rounding of the distances of the file is used unless another one is given
+  fn read_vrp_file(content: &str, rounding: &mut Rounding) -> UnsafeDataset {
+    let file = cvrplib::parse(content);
+    if *rounding == Rounding::None {
+      *rounding = file.rounding;
+    }
+    let distance_matrix = file.distance_matrix.into_iter().map(|row| row.into_iter().map(Some).collect()).collect();
+    UnsafeDataset {
+      depot: file.depot,
+      capacity: file.capacity,
+      demands: file.demands,
+      ..UnsafeDataset::new(file.labels, distance_matrix, file.locations)
+    }
+  }
+
+  // load a dataset in the CSV format (locations or a distance matrix) into an unsafe dataset
+  fn read_csv_file(content: &str, delimiter: char) -> UnsafeDataset {
+    let file = csv::parse(content, delimiter);
+    UnsafeDataset::new(file.labels, file.distance_matrix, file.locations)
+  }
+
   // function that allows to load a dataset from a file
-  pub fn from_file(file_name: &str) -> Self {
+  pub fn from_file(file_name: &str, options: &DatasetOptions) -> Self {
     // verify that the given file exists
     if !Path::new(file_name).exists() {
       panic!("The given file does not exist");
     }
 
     // load the dataset into RAM as a string
-    let json_dataset = read_to_string(file_name).expect("Unable to read the dataset file");
-
-    // parse the dataset into an unsafe dataset
-    let unsafe_dataset: UnsafeDataset = serde_json::from_str(&json_dataset).expect("Unable to parse the dataset file");
+    let content = read_to_string(file_name).expect("Unable to read the dataset file");
+
+    // parse the dataset into an unsafe dataset, TSPLIB, CVRPLIB and CSV files being recognized by their extension
+    let mut rounding = options.rounding;
+    let unsafe_dataset: UnsafeDataset = match Path::new(file_name).extension().and_then(|extension| extension.to_str()) {
+      Some("vrp") | Some("tsp") => Self::read_vrp_file(&content, &mut rounding),
+      Some("csv") => Self::read_csv_file(&content, options.csv_delimiter),
+      _ => serde_json::from_str(&content).expect("Unable to parse the dataset file")
+    };
 
     // create the matrix object
     let matrix = {
       let size = unsafe_dataset.locations.len();
       if size == 0 {
-        Matrix::Distances(unsafe_dataset.distance_matrix)
+        // the ATT distance is computed from the coordinates, a distance matrix can't be rounded with it
+        if rounding == Rounding::Att {
+          panic!("The ATT rounding can only be used with datasets made of locations, this dataset is a distance matrix");
+        }
+        let rows: Vec<Vec<f64>> = unsafe_dataset.distance_matrix.iter()
+          .map(|row| row.iter().map(|distance| distance.unwrap_or(f64::INFINITY)).collect())
+          .collect();
+        Matrix::from_rows(&rows, options.precision, options.layout, rounding)
       } else {
-        Matrix::Locations(unsafe_dataset.locations)
+        Matrix::Locations(unsafe_dataset.locations, rounding)
       }
     };
 
+    // create the constraints on the order of the nodes
+    let label_index = LabelIndex::new(&unsafe_dataset.labels);
+    let constraints = Constraints::new(&unsafe_dataset.labels, &unsafe_dataset.fixed_edges, &unsafe_dataset.fixed_segments, &unsafe_dataset.precedences);
+
+    // create the time windows of the nodes
+    let time_windows = TimeWindows::new(&unsafe_dataset.labels, unsafe_dataset.time_windows, unsafe_dataset.service_times, options.time_windows_mode, options.lateness_penalty);
+
+    // create the fleet of salesmen or vehicles
+    let depot = Self::find_depot(&label_index, unsafe_dataset.depot.as_ref(), options);
+    let fleet = Self::build_fleet(&unsafe_dataset.labels, depot, unsafe_dataset.capacity, unsafe_dataset.demands, options);
+
+    // create the orienteering problem if the nodes have prizes
+    let orienteering = match (unsafe_dataset.budget, unsafe_dataset.prizes.is_empty()) {
+      (Some(budget), false) => Some(Orienteering::new(&unsafe_dataset.labels, depot, unsafe_dataset.prizes, budget)),
+      (None, true) => None,
+      (None, false) => panic!("The dataset gives the prizes of the nodes, but not the budget of the tour"),
+      (Some(_), true) => panic!("The dataset gives the budget of the tour, but not the prizes of the nodes")
+    };
+
+    // create the clusters of nodes
+    let clusters = Clusters::new(&unsafe_dataset.labels, &unsafe_dataset.clusters);
+
+    // create the other cost matrices, stored like the distances
+    let cost_matrices = unsafe_dataset.cost_matrices.into_iter()
+      .map(|(name, rows)| {
+        let cost_matrix = Matrix::from_rows(&rows, options.precision, options.layout, Rounding::None);
+        (name, cost_matrix)
+      })
+      .collect();
+
+    // create the costs of the nodes
+    let node_costs = NodeCosts::new(&unsafe_dataset.labels, unsafe_dataset.node_costs, unsafe_dataset.skip_penalties);
+
     // create a new dataset object
-    Self::new(unsafe_dataset.labels, matrix)
+    let mut dataset = Self::new(unsafe_dataset.labels, matrix, constraints, Variants { time_windows, fleet, orienteering, clusters, cost_matrices, node_costs }, options);
+    dataset.rounding = rounding;
+    dataset
   }
 }
 
@@ -0,0 +1,113 @@
+use rand::prelude::*;
+use crate::dataset::{Dataset,DatasetOptions,Matrix};
+use crate::generation::Generation;
+
+// define a node inserted into a dataset : its location for datasets made of locations,
+// or its distances from and to every other node (in the order of the labels) for distance matrices
+pub enum NewNode {
+  Location(f64, f64),
+  Distances { from: Vec<f64>, to: Vec<f64> }
+}
+
+// verify that the nodes of a dataset can be updated, which rebuilds the dataset without its constraints and variants
+fn verify_updatable(dataset: &Dataset) {
+  if !dataset.constraints.segments.is_empty() || !dataset.constraints.precedences.is_empty() || dataset.time_windows.is_some()
+    || dataset.fleet.is_some() || dataset.orienteering.is_some() || dataset.clusters.is_some() || !dataset.cost_matrices.is_empty() || dataset.node_costs.is_some() {
+    panic!("Nodes can only be inserted into or removed from datasets without fixed edges, precedences, time windows, salesmen, vehicles, prizes, clusters, cost matrices or node costs");
+  }
+}
+
+// build a dataset made of the given nodes of a dataset, plus a new node if any
+// the distances are rounded like the ones of the dataset, whatever the rounding of the options
+fn rebuild(dataset: &Dataset, kept_nodes: &[usize], new_node: Option<(String, NewNode)>, options: &DatasetOptions) -> Dataset {
+  verify_updatable(dataset);
+  let mut labels = dataset.labels_of(kept_nodes);
+
+  let matrix = match &dataset.spatial_index {
+    // the locations are kept by the spatial index, whatever the cache of the distances
+    Some(spatial_index) => {
+      let mut locations: Vec<(f64, f64)> = kept_nodes.iter().map(|&node| spatial_index.location(node)).collect();
+      match new_node {
+        Some((label, NewNode::Location(x, y))) => {
+          labels.push(label);
+          locations.push((x, y));
+        },
+        Some(_) => panic!("A node inserted into a dataset made of locations should be given by its location"),
+        None => {}
+      }
+      Matrix::Locations(locations, dataset.rounding)
+    },
+    None => {
+      let mut rows: Vec<Vec<f64>> = kept_nodes.iter()
+        .map(|&node1| kept_nodes.iter().map(|&node2| dataset.distance_matrix.get(node1, node2)).collect())
+        .collect();
+      match new_node {
+        Some((label, NewNode::Distances { from, to })) => {
+          if from.len() != kept_nodes.len() || to.len() != kept_nodes.len() {
+            panic!("The node {} should have one distance from and to each of the {} nodes of the dataset", label, kept_nodes.len());
+          }
+          labels.push(label);
+          for (row, &distance) in rows.iter_mut().zip(to.iter()) {
+            row.push(distance);
+          }
+          let mut new_row = from;
+          new_row.push(0.0);
+          rows.push(new_row);
+        },
+        Some(_) => panic!("A node inserted into a distance matrix should be given by its distances"),
+        None => {}
+      }
+      Matrix::from_rows(&rows, options.precision, options.layout, dataset.rounding)
+    }
+  };
+
+  let mut rebuilt = Dataset::from_matrix(labels, matrix, options);
+  rebuilt.rounding = dataset.rounding;
+  rebuilt
+}
+
+// returns a copy of a dataset with a new node, placed after the others
+pub fn insert_node(dataset: &Dataset, label: &str, node: NewNode, options: &DatasetOptions) -> Dataset {
+  let kept_nodes: Vec<usize> = (0..dataset.size).collect();
+  rebuild(dataset, &kept_nodes, Some((label.to_string(), node)), options)
+}
+
+// returns a copy of a dataset without a node, the following nodes moving one index down
+pub fn remove_node(dataset: &Dataset, label: &str, options: &DatasetOptions) -> Dataset {
+  let removed = dataset.node(label).unwrap_or_else(|error| panic!("Unable to remove the node : {}", error));
+  let kept_nodes: Vec<usize> = (0..dataset.size).filter(|&node| node != removed).collect();
+  rebuild(dataset, &kept_nodes, None, options)
+}
+
+// adapt a tour of a dataset to an updated version of the dataset, nodes being matched by their labels
+// the removed nodes are spliced out of the tour, and the new nodes are inserted where they lengthen the path the least
+pub fn repair_tour(previous_dataset: &Dataset, dataset: &Dataset, nodes: &[usize]) -> Vec<usize> {
+  let mut tour: Vec<usize> = nodes.iter().filter_map(|&node| dataset.label_index().get(&previous_dataset.labels()[node])).collect();
+
+  let mut visited = vec![false; dataset.size];
+  for &node in tour.iter() {
+    visited[node] = true;
+  }
+  for node in (0..dataset.size).filter(|&node| !visited[node]) {
+    let matrix = &dataset.distance_matrix;
+    let cost = |position: usize| match (position.checked_sub(1).map(|previous| tour[previous]), tour.get(position)) {
+      (Some(previous), Some(&next)) => matrix.get(previous, node) + matrix.get(node, next) - matrix.get(previous, next),
+      (Some(previous), None) => matrix.get(previous, node),
+      (None, Some(&next)) => matrix.get(node, next),
+      (None, None) => 0.0
+    };
+    let position = (0..=tour.len())
+      .min_by(|&position1, &position2| cost(position1).partial_cmp(&cost(position2)).expect("Error while comparing insertion positions"))
+      .expect("Unable to find an insertion position");
+    tour.insert(position, node);
+  }
+  tour
+}
+
+// returns the next generation of a generation once its dataset has been updated, made of its repaired individuals (warm start)
+pub fn warm_generation<'a>(previous_generation: &Generation, dataset: &'a Dataset, rng: &mut ThreadRng) -> Generation<'a> {
+  let tours = previous_generation.population.iter()
+    .map(|individual| (repair_tour(individual.dataset, dataset, &individual.nodes), dataset.size))
+    .collect();
+  Generation::from_tours(previous_generation.id + 1, previous_generation.number_of_generations, previous_generation.population_size, dataset, tours, rng)
+}
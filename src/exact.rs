@@ -0,0 +1,591 @@
+use std::fmt::Display;
+use clap::ValueEnum;
+use crate::bounds::{minimum_one_tree,minimum_spanning_tree,subgradient_ascent,Costs,HELD_KARP_ITERATIONS};
+use crate::dataset::Dataset;
+use crate::utils::ThousandsDisplayPolicy;
+
+// above this number of nodes, the Held-Karp table doesn't fit in memory anymore
+// and the branch-and-bound is used instead
+pub const HELD_KARP_MAX_SIZE: usize = 20;
+
+// number of search nodes explored by the branch-and-bound before it stops and returns the best tour found
+pub const DEFAULT_NODE_LIMIT: usize = 1_000_000;
+
+// number of subgradient iterations refining the node penalties at the root and at the other nodes of the branch-and-bound,
+// and number of iterations without improvement after which the step is halved
+const ROOT_ASCENT_ITERATIONS: usize = 1000;
+const ROOT_ASCENT_PATIENCE: usize = 20;
+const NODE_ASCENT_ITERATIONS: usize = 10;
+const NODE_ASCENT_PATIENCE: usize = 2;
+
+// number of nearest neighbor tours improved to get the initial upper bound of the branch-and-bound
+const INITIAL_TOURS_COUNT: usize = 5;
+
+// define the kind of tour the exact solver should optimize
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TourKind {
+  // a path visiting each node once (same objective as the genetic algorithm)
+  Open,
+  // a cycle going back to its first node at the end
+  Closed
+}
+
+// define the structure of an exact (optimal) solution
+pub struct ExactSolution<'a> {
+  pub kind: TourKind,
+  pub nodes: Vec<usize>,
+  pub dataset: &'a Dataset,
+  pub length: f64,
+  // false if the branch-and-bound was stopped by its node limit, the tour being the best one found
+  pub optimal: bool
+}
+
+// copy the distances in a dense matrix, as the solvers read them a lot
+// edges conflicting with a fixed edge are forbidden, so the solvers only find tours using every fixed edge
+fn dense_costs(dataset: &Dataset) -> Vec<Vec<f64>> {
+  let constraints = &dataset.constraints;
+  (0..dataset.size)
+    .map(|i| (0..dataset.size).map(|j| {
+      let conflicting = constraints.fixed_successor(i).is_some_and(|successor| successor != j)
+        || constraints.fixed_predecessor(j).is_some_and(|predecessor| predecessor != i);
+      if conflicting && i != j { f64::INFINITY } else { dataset.distance_matrix.get(i, j) }
+    }).collect())
+    .collect()
+}
+
+// returns the cost of starting and of ending an open tour at each node
+// a node with a fixed predecessor or that must follow other nodes can't start the tour,
+// and a node with a fixed successor can't end it
+fn open_ends_costs(dataset: &Dataset) -> (Vec<f64>, Vec<f64>) {
+  let constraints = &dataset.constraints;
+  let cost = |fixed: bool| if fixed { f64::INFINITY } else { 0.0 };
+  (
+    (0..dataset.size).map(|node| cost(constraints.fixed_predecessor(node).is_some() || !constraints.required_before(node).is_empty())).collect(),
+    (0..dataset.size).map(|node| cost(constraints.fixed_successor(node).is_some())).collect()
+  )
+}
+
+// compute the length of a tour of the given kind
+fn tour_length(costs: &[Vec<f64>], nodes: &[usize], kind: TourKind) -> f64 {
+  let mut length: f64 = nodes.windows(2).map(|edge| costs[edge[0]][edge[1]]).sum();
+  if kind == TourKind::Closed {
+    length += costs[nodes[nodes.len() - 1]][nodes[0]];
+  }
+  length
+}
+
+// verify that the exact solvers can be used on a dataset
+pub fn verify_supported(dataset: &Dataset) {
+  if dataset.time_windows.is_some() || dataset.fleet.is_some() || dataset.orienteering.is_some() || dataset.clusters.is_some() || dataset.node_costs.is_some() {
+    panic!("The exact solvers can't be used with time windows, several salesmen, prizes, clusters or node costs");
+  }
+}
+
+// find the optimal tour using the best suited algorithm for the dataset size
+// the branch-and-bound explores at most node_limit search nodes, returning the best tour found if it reaches the limit
+// panics if every tour of the given kind uses a forbidden edge, or if the node limit is reached before finding a feasible tour
+pub fn solve(dataset: &Dataset, kind: TourKind, node_limit: usize) -> ExactSolution<'_> {
+  verify_supported(dataset);
+
+  let solution = if dataset.size <= HELD_KARP_MAX_SIZE {
+    held_karp(dataset, kind)
+  } else {
+    branch_and_bound(dataset, kind, node_limit)
+  };
+
+  if !solution.length.is_finite() && !solution.optimal {
+    panic!("The node limit was reached without a feasible {} tour : increase the node limit", match kind { TourKind::Open => "open", TourKind::Closed => "closed" });
+  }
+  if !solution.length.is_finite() {
+    panic!("No feasible {} tour exists : every tour uses a forbidden edge", match kind { TourKind::Open => "open", TourKind::Closed => "closed" });
+  }
+  solution
+}
+
+// Held-Karp dynamic programming, in O(2^n * n^2) time and O(2^n * n) memory
+// each state (subset, last) holds the length of the shortest path visiting the subset and ending at last
+pub fn held_karp(dataset: &Dataset, kind: TourKind) -> ExactSolution<'_> {
+  let costs = dense_costs(dataset);
+  let (start_costs, end_costs) = open_ends_costs(dataset);
+  let size = dataset.size;
+
+  if size > HELD_KARP_MAX_SIZE {
+    panic!("The Held-Karp algorithm can't be used with more than {} nodes", HELD_KARP_MAX_SIZE);
+  }
+
+  // closed tours always start at the first node, so it isn't part of the subsets
+  // open tours can start anywhere, so every node is part of the subsets
+  let offset = match kind {
+    TourKind::Open => 0,
+    TourKind::Closed => 1
+  };
+  let bits = size - offset;
+  let full = (1usize << bits) - 1;
+
+  // nodes that must be visited before each node, as a subset
+  // closed tours start at the first node, so it is always visited before the others
+  let required_masks: Vec<usize> = (0..bits)
+    .map(|node| dataset.constraints.required_before(node + offset).iter()
+      .filter(|&&before| before >= offset)
+      .fold(0, |mask, &before| mask | (1 << (before - offset))))
+    .collect();
+
+  // create the tables of lengths and predecessors
+  let mut lengths = vec![f64::INFINITY; (full + 1) * bits];
+  let mut parents = vec![u8::MAX; (full + 1) * bits];
+
+  // initialize the paths made of a single node
+  for last in 0..bits {
+    lengths[(1 << last) * bits + last] = match kind {
+      _ if required_masks[last] != 0 => f64::INFINITY,
+      TourKind::Open => start_costs[last],
+      TourKind::Closed => costs[0][last + offset]
+    };
+  }
+
+  // extend each path by one node, subsets are processed in increasing order
+  for subset in 1..=full {
+    for last in 0..bits {
+      let length = lengths[subset * bits + last];
+      if subset & (1 << last) == 0 || length == f64::INFINITY {
+        continue;
+      }
+
+      for next in 0..bits {
+        if subset & (1 << next) != 0 || required_masks[next] & !subset != 0 {
+          continue;
+        }
+
+        let next_subset = subset | (1 << next);
+        let next_length = length + costs[last + offset][next + offset];
+        if next_length < lengths[next_subset * bits + next] {
+          lengths[next_subset * bits + next] = next_length;
+          parents[next_subset * bits + next] = last as u8;
+        }
+      }
+    }
+  }
+
+  // find the best last node, closing the tour if needed
+  let closing_cost = |last: usize| match kind {
+    TourKind::Open => end_costs[last],
+    TourKind::Closed => costs[last + offset][0]
+  };
+  let mut best_last = 0;
+  for last in 1..bits {
+    if lengths[full * bits + last] + closing_cost(last) < lengths[full * bits + best_last] + closing_cost(best_last) {
+      best_last = last;
+    }
+  }
+
+  // walk back the predecessors to rebuild the tour
+  let mut nodes = Vec::with_capacity(size);
+  let mut subset = full;
+  let mut last = best_last;
+  loop {
+    nodes.push(last + offset);
+    let parent = parents[subset * bits + last];
+    subset &= !(1 << last);
+    if parent == u8::MAX {
+      break;
+    }
+    last = parent as usize;
+  }
+  if kind == TourKind::Closed {
+    nodes.push(0);
+  }
+  nodes.reverse();
+
+  // the tour is incomplete if no tour satisfies the constraints
+  let length = if lengths[full * bits + best_last] + closing_cost(best_last) < f64::INFINITY {
+    tour_length(&costs, &nodes, kind)
+  } else {
+    f64::INFINITY
+  };
+  ExactSolution { kind, nodes, dataset, length, optimal: true }
+}
+
+// define the state shared during the branch-and-bound search
+// the bounds use the node penalties of the Held-Karp bound : adding a penalty to every edge of a node
+// adds the same amount to every tour, so the bounds of the modified costs stay valid once the penalties are removed
+// the penalties found for the whole dataset are refined at each search node by a few more subgradient iterations
+struct Search {
+  kind: TourKind,
+  costs: Vec<Vec<f64>>,
+  symmetric_costs: Vec<Vec<f64>>,
+  end_costs: Vec<f64>,
+  required_before: Vec<Vec<usize>>,
+  path: Vec<usize>,
+  visited: Vec<bool>,
+  best_nodes: Vec<usize>,
+  best_length: f64,
+  explored_nodes: usize,
+  node_limit: usize
+}
+
+// define a lower bound of the length needed to complete a path, with the degree of each unvisited node in the relaxation
+struct PathBound {
+  bound: f64,
+  degrees: Vec<usize>
+}
+
+impl Search {
+  // compute a 1-tree lower bound of the length needed to complete the current path, with the given node penalties:
+  // a spanning tree of the unvisited nodes, plus the cheapest edges connecting it to the path ends
+  // open tours are closed by a dummy node, reached from the nodes that can end the tour
+  fn lower_bound(&self, remaining: &[usize], penalties: &[f64]) -> PathBound {
+    let last = self.path[self.path.len() - 1];
+    let first = self.path[0];
+    let mut degrees = vec![0; self.costs.len()];
+
+    if remaining.is_empty() {
+      let bound = match self.kind {
+        TourKind::Open => self.end_costs[last],
+        TourKind::Closed => self.costs[last][first]
+      };
+      return PathBound { bound, degrees };
+    }
+
+    let closing_cost = |node: usize| match self.kind {
+      TourKind::Open => self.end_costs[node],
+      TourKind::Closed => self.costs[node][first]
+    };
+
+    // spanning tree of the unvisited nodes, with the modified costs
+    let tree = minimum_spanning_tree(remaining.len(), |i, j| self.symmetric_costs[remaining[i]][remaining[j]] + penalties[remaining[i]] + penalties[remaining[j]]);
+    for &(parent, child, _) in tree.iter() {
+      degrees[remaining[parent]] += 1;
+      degrees[remaining[child]] += 1;
+    }
+
+    // with several unvisited nodes, the path is completed by leaving the last node and reaching the end from different nodes
+    let cheapest = |cost: &dyn Fn(usize) -> f64| remaining.iter()
+      .map(|&node| (cost(node) + penalties[node], node))
+      .fold((f64::INFINITY, usize::MAX), |best, candidate| if candidate.0 < best.0 { candidate } else { best });
+    let (mut leaving, mut leaving_node) = cheapest(&|node| self.costs[last][node]);
+    let (mut closing, mut closing_node) = cheapest(&closing_cost);
+    if remaining.len() > 1 && leaving_node == closing_node && leaving_node != usize::MAX {
+      let second_leaving = cheapest(&|node| if node == leaving_node { f64::INFINITY } else { self.costs[last][node] });
+      let second_closing = cheapest(&|node| if node == closing_node { f64::INFINITY } else { closing_cost(node) });
+      if leaving + second_closing.0 <= second_leaving.0 + closing {
+        (closing, closing_node) = second_closing;
+      } else {
+        (leaving, leaving_node) = second_leaving;
+      }
+    }
+    for node in [leaving_node, closing_node].into_iter().filter(|&node| node != usize::MAX) {
+      degrees[node] += 1;
+    }
+
+    // each unvisited node has two edges in the completed path
+    let bound = tree.iter().map(|edge| edge.2).sum::<f64>() + leaving + closing - 2.0 * remaining.iter().map(|&node| penalties[node]).sum::<f64>();
+    PathBound { bound, degrees }
+  }
+
+  // improve the node penalties by subgradient ascent on the bound of the current path, starting from the given ones
+  // stops as soon as the branch can be pruned, and returns the best bound found
+  fn refine_bound(&self, remaining: &[usize], length: f64, penalties: &mut Vec<f64>) -> f64 {
+    let mut path_bound = self.lower_bound(remaining, penalties);
+    let mut best_bound = path_bound.bound;
+    let mut best_penalties = penalties.clone();
+    let target = self.best_length - length;
+    let mut scale = 2.0;
+    let mut stalled = 0;
+    let (iterations, patience) = if self.path.len() == 1 {
+      (ROOT_ASCENT_ITERATIONS, ROOT_ASCENT_PATIENCE)
+    } else {
+      (NODE_ASCENT_ITERATIONS, NODE_ASCENT_PATIENCE)
+    };
+    for _ in 0..iterations {
+      if length + best_bound >= self.best_length || !target.is_finite() {
+        break;
+      }
+
+      // a bound where every unvisited node has a degree of 2 is the length of a path completing the tour
+      let norm: f64 = remaining.iter().map(|&node| (path_bound.degrees[node] as f64 - 2.0).powi(2)).sum();
+      if norm == 0.0 {
+        break;
+      }
+      let step = scale * (target - path_bound.bound).max(0.0) / norm;
+      for &node in remaining.iter() {
+        penalties[node] += step * (path_bound.degrees[node] as f64 - 2.0);
+      }
+
+      path_bound = self.lower_bound(remaining, penalties);
+      if path_bound.bound > best_bound {
+        best_bound = path_bound.bound;
+        best_penalties.clone_from(penalties);
+        stalled = 0;
+      } else {
+        stalled += 1;
+        if stalled >= patience {
+          scale /= 2.0;
+          stalled = 0;
+        }
+      }
+    }
+    *penalties = best_penalties;
+    best_bound
+  }
+
+  // explore every way to complete the current path, pruning the ones that can't beat the best tour
+  fn explore(&mut self, length: f64, mut penalties: Vec<f64>) {
+    // stop once the node limit is reached, keeping the best tour found
+    if self.explored_nodes >= self.node_limit {
+      return;
+    }
+    self.explored_nodes += 1;
+
+    let remaining: Vec<usize> = (0..self.visited.len()).filter(|&node| !self.visited[node]).collect();
+
+    // the path is complete
+    if remaining.is_empty() {
+      let length = length + self.lower_bound(&remaining, &penalties).bound;
+      if length < self.best_length {
+        self.best_length = length;
+        self.best_nodes = self.path.clone();
+      }
+      return;
+    }
+
+    // prune the branch if it can't improve the best tour
+    if length + self.refine_bound(&remaining, length, &mut penalties) >= self.best_length {
+      return;
+    }
+
+    // try the closest nodes first, as they are more likely to lead to good tours
+    let last = self.path[self.path.len() - 1];
+    let mut candidates: Vec<usize> = remaining.into_iter()
+      .filter(|&node| self.required_before[node].iter().all(|&before| self.visited[before]))
+      .collect();
+    candidates.sort_by(|&n1, &n2| self.costs[last][n1].partial_cmp(&self.costs[last][n2]).expect("Error while sorting candidates"));
+
+    for next in candidates {
+      self.visited[next] = true;
+      self.path.push(next);
+      self.explore(length + self.costs[last][next], penalties.clone());
+      self.path.pop();
+      self.visited[next] = false;
+    }
+  }
+}
+
+// build a tour by always going to the closest unvisited node
+fn nearest_neighbor_tour(costs: &[Vec<f64>], start: usize) -> Vec<usize> {
+  let mut visited = vec![false; costs.len()];
+  let mut nodes = vec![start];
+  visited[start] = true;
+
+  for _ in 1..costs.len() {
+    let last = nodes[nodes.len() - 1];
+    let next = (0..costs.len())
+      .filter(|&node| !visited[node])
+      .min_by(|&n1, &n2| costs[last][n1].partial_cmp(&costs[last][n2]).expect("Error while building a nearest neighbor tour"))
+      .expect("Unable to find an unvisited node");
+    visited[next] = true;
+    nodes.push(next);
+  }
+
+  nodes
+}
+
+// returns whether every node of a tour is visited after the nodes it must follow
+fn respects_precedences(precedences: &[(usize, usize)], nodes: &[usize]) -> bool {
+  let mut positions = vec![0; nodes.len()];
+  for (position, &node) in nodes.iter().enumerate() {
+    positions[node] = position;
+  }
+  precedences.iter().all(|&(before, after)| positions[before] < positions[after])
+}
+
+// improve a tour with 2-opt moves (reversing a segment) and or-opt moves (moving a segment of up to 3 nodes earlier)
+// until none of them shortens it, each move being evaluated by the change of length it brings
+// forbidden edges cost more than any tour without them, so that they are removed first,
+// and the moves breaking a precedence of a tour respecting them are undone
+// open tours pay the cost of their ends, closed tours keep their first node in place and go back to it
+fn improve_tour(costs: &[Vec<f64>], ends_costs: Option<(&[f64], &[f64])>, precedences: &[(usize, usize)], nodes: &mut [usize]) {
+  let size = nodes.len();
+  let largest_cost = costs.iter().flatten().copied().filter(|cost| cost.is_finite()).fold(0.0, f64::max);
+  let penalty = 1.0 + size as f64 * largest_cost;
+  let finite = |cost: f64| if cost.is_finite() { cost } else { penalty };
+  // the changes of length below the rounding errors of the penalties aren't improvements
+  let tolerance = 1e-12 * penalty * size as f64;
+
+  // cost of an edge between two consecutive positions, None being the outside of an open tour
+  let link = |from: Option<usize>, to: Option<usize>| match (from, to, ends_costs) {
+    (Some(from), Some(to), _) => finite(costs[from][to]),
+    (None, Some(to), Some((start_costs, _))) => finite(start_costs[to]),
+    (Some(from), None, Some((_, end_costs))) => finite(end_costs[from]),
+    _ => 0.0
+  };
+  let first = if ends_costs.is_some() { 0 } else { 1 };
+  let before = |nodes: &[usize], position: usize| if position > 0 { Some(nodes[position - 1]) } else { None };
+  let after = |nodes: &[usize], position: usize| match (position + 1 < size, ends_costs) {
+    (true, _) => Some(nodes[position + 1]),
+    (false, None) => Some(nodes[0]),
+    (false, Some(_)) => None
+  };
+
+  let mut feasible = respects_precedences(precedences, nodes);
+  // keep a move if it doesn't break a precedence of a feasible tour, undo it otherwise
+  let mut keep = |nodes: &[usize]| {
+    let respected = precedences.is_empty() || respects_precedences(precedences, nodes);
+    let kept = respected || !feasible;
+    feasible = feasible || respected;
+    kept
+  };
+
+  let mut improved = true;
+  while improved {
+    improved = false;
+    // length of the path up to each position, forward and backward, to evaluate reversals in constant time
+    let prefix_lengths = |nodes: &[usize], reversed: bool| -> Vec<f64> {
+      let mut lengths = vec![0.0; size];
+      for position in 1..size {
+        let (from, to) = if reversed { (nodes[position], nodes[position - 1]) } else { (nodes[position - 1], nodes[position]) };
+        lengths[position] = lengths[position - 1] + finite(costs[from][to]);
+      }
+      lengths
+    };
+    let (mut forward, mut backward) = (prefix_lengths(nodes, false), prefix_lengths(nodes, true));
+
+    for start in first..size {
+      for end in start + 1..size {
+        let (previous, next) = (before(nodes, start), after(nodes, end));
+        let (start_node, end_node) = (nodes[start], nodes[end]);
+        let delta = link(previous, Some(end_node)) + link(Some(start_node), next) - link(previous, Some(start_node)) - link(Some(end_node), next)
+          + (backward[end] - backward[start]) - (forward[end] - forward[start]);
+        if delta < -tolerance {
+          nodes[start..=end].reverse();
+          if keep(nodes) {
+            improved = true;
+            (forward, backward) = (prefix_lengths(nodes, false), prefix_lengths(nodes, true));
+          } else {
+            nodes[start..=end].reverse();
+          }
+        }
+
+        for segment_length in 1..=3.min(size - end) {
+          let last = end + segment_length - 1;
+          let (previous, next) = (before(nodes, start), after(nodes, last));
+          let (start_node, end_node, last_node, node_before_segment) = (nodes[start], nodes[end], nodes[last], nodes[end - 1]);
+          let delta = link(previous, Some(end_node)) + link(Some(last_node), Some(start_node)) + link(Some(node_before_segment), next)
+            - link(previous, Some(start_node)) - link(Some(node_before_segment), Some(end_node)) - link(Some(last_node), next);
+          if delta < -tolerance {
+            nodes[start..=last].rotate_right(segment_length);
+            if keep(nodes) {
+              improved = true;
+              (forward, backward) = (prefix_lengths(nodes, false), prefix_lengths(nodes, true));
+            } else {
+              nodes[start..=last].rotate_left(segment_length);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+// depth first branch-and-bound, pruned with 1-tree lower bounds using the node penalties of the Held-Karp bound
+// asymmetric datasets are supported: the spanning trees use the cheapest direction of each edge
+// the search stops after node_limit search nodes, the solution then being the best tour found instead of an optimal one
+pub fn branch_and_bound(dataset: &Dataset, kind: TourKind, node_limit: usize) -> ExactSolution<'_> {
+  let costs = dense_costs(dataset);
+  let size = dataset.size;
+  let symmetric_costs = (0..size)
+    .map(|i| (0..size).map(|j| costs[i][j].min(costs[j][i])).collect())
+    .collect();
+
+  let (start_costs, end_costs) = open_ends_costs(dataset);
+
+  // closed tours can start anywhere, so only the first node is tried
+  let starts: Vec<usize> = match kind {
+    TourKind::Open => (0..size).filter(|&node| start_costs[node] == 0.0).collect(),
+    TourKind::Closed => vec![0]
+  };
+
+  // use the best nearest neighbor tour satisfying the constraints as the initial upper bound
+  let initial_length = |nodes: &[usize]| match dataset.constraints.check(dataset.labels(), &dataset.distance_matrix, nodes) {
+    Ok(()) => tour_length(&costs, nodes, kind),
+    Err(_) => f64::INFINITY
+  };
+  // a few tours are built from evenly spread nodes and improved, closed tours keeping their first node out of the moves
+  let ends_costs = match kind {
+    TourKind::Open => Some((start_costs.as_slice(), end_costs.as_slice())),
+    TourKind::Closed => None
+  };
+  let improved_tour = |start: usize| {
+    let mut nodes = nearest_neighbor_tour(&costs, start);
+    dataset.constraints.repair(&mut nodes);
+    if kind == TourKind::Closed {
+      let first = nodes.iter().position(|&node| node == 0).expect("Unable to find the first node");
+      nodes.rotate_left(first);
+    }
+    improve_tour(&costs, ends_costs, &dataset.constraints.precedences, &mut nodes);
+    nodes
+  };
+  let best_nodes = (0..size)
+    .step_by(size.div_ceil(INITIAL_TOURS_COUNT))
+    .map(improved_tour)
+    .min_by(|t1, t2| initial_length(t1).partial_cmp(&initial_length(t2)).expect("Error while comparing initial tours"))
+    .expect("Unable to build an initial tour");
+  let best_length = initial_length(&best_nodes);
+
+  // optimize the node penalties once, sizing the subgradient steps with the initial tour (or a rough estimate without one)
+  let bound_costs = Costs::new(&dataset.distance_matrix, kind);
+  let upper_bound = if best_length.is_finite() { best_length } else { 2.0 * minimum_one_tree(&bound_costs, &vec![0.0; bound_costs.size]).weight };
+  let penalties = match upper_bound.is_finite() {
+    true => subgradient_ascent(&bound_costs, upper_bound, HELD_KARP_ITERATIONS).1,
+    false => vec![0.0; bound_costs.size]
+  };
+
+  let mut search = Search {
+    kind,
+    costs,
+    symmetric_costs,
+    end_costs,
+    required_before: (0..size).map(|node| dataset.constraints.required_before(node).to_vec()).collect(),
+    path: Vec::with_capacity(size),
+    visited: vec![false; size],
+    best_nodes,
+    best_length,
+    explored_nodes: 0,
+    node_limit
+  };
+
+  // explore the tours from each possible start
+  for start in starts {
+    search.visited[start] = true;
+    search.path.push(start);
+    search.explore(0.0, penalties.clone());
+    search.path.pop();
+    search.visited[start] = false;
+  }
+
+  let optimal = search.explored_nodes < search.node_limit;
+  ExactSolution { kind, length: search.best_length, nodes: search.best_nodes, dataset, optimal }
+}
+
+// implement the Display trait for the ExactSolution struct
+impl<'a> Display for ExactSolution<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // for each node in the tour, add it to the string
+    let mut result = self.nodes.iter()
+      .map(|&node| format!("{:>width$}", self.dataset.labels()[node], width = self.dataset.longest_label_display_width))
+      .collect::<Vec<String>>()
+      .join(" -> ");
+
+    // closed tours go back to the first node
+    if self.kind == TourKind::Closed {
+      result.push_str(&format!(" -> {:>width$}", self.dataset.labels()[self.nodes[0]], width = self.dataset.longest_label_display_width));
+    }
+
+    // add the length of the tour
+    result.push_str(&format!(" · {:>width$}", self.length.thousands(), width = self.dataset.longest_path_display_width));
+    if !self.optimal {
+      result.push_str(" · the node limit was reached, this tour may not be optimal");
+    }
+
+    write!(f, "{}", result)
+  }
+}
@@ -0,0 +1,177 @@
+use rand::prelude::*;
+use crate::constraints::Constraints;
+use crate::dataset::{Labels,Matrix};
+
+// maximum number of steps (nodes added to the path) of the search proving that the dataset has a feasible tour
+pub const FEASIBLE_SEARCH_MAX_STEPS: usize = 10_000_000;
+
+// maximum number of steps of the search building a random feasible tour, per node
+pub const RANDOM_SEARCH_STEPS_PER_NODE: usize = 10;
+
+// define the outcome of a search for a feasible tour
+enum SearchOutcome {
+  Found(Vec<usize>),
+  // every path from the start has been explored, none of them visits every node
+  Exhausted,
+  // the search ran out of steps
+  Aborted
+}
+
+// define the graph of the allowed edges of a dataset with forbidden (infinite) edges
+// a tour is feasible when it only uses allowed edges
+pub struct AllowedEdges {
+  successors: Vec<Vec<usize>>,
+  predecessors: Vec<Vec<usize>>,
+  // nodes that must be visited before each node
+  required_before: Vec<Vec<usize>>,
+  // nodes that can start a tour (the only node without predecessors if there is one)
+  starts: Vec<usize>,
+  // a feasible tour found while loading the dataset
+  pub feasible_tour: Vec<usize>
+}
+
+impl AllowedEdges {
+  // build the graph of the allowed edges, returns None if every edge is allowed
+  // edges conflicting with a fixed edge are left out of the graph, so its tours use every fixed edge,
+  // and nodes are only visited once the nodes they must follow are
+  // panics with the reason if no feasible tour exists
+  pub fn new(labels: &Labels, distance_matrix: &Matrix, constraints: &Constraints, rng: &mut ThreadRng) -> Option<Self> {
+    // locations are always connected
+    if let Matrix::Locations(..) | Matrix::CachedLocations(_) = distance_matrix {
+      return None;
+    }
+
+    let size = distance_matrix.len();
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); size];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); size];
+    let mut forbidden_edges = false;
+    for (node1, node1_successors) in successors.iter_mut().enumerate() {
+      for node2 in (0..size).filter(|&node2| node2 != node1) {
+        if !distance_matrix.get(node1, node2).is_finite() {
+          forbidden_edges = true;
+        } else if constraints.fixed_successor(node1).is_none_or(|successor| successor == node2)
+          && constraints.fixed_predecessor(node2).is_none_or(|predecessor| predecessor == node1) {
+          node1_successors.push(node2);
+          predecessors[node2].push(node1);
+        }
+      }
+    }
+    if !forbidden_edges {
+      return None;
+    }
+
+    // a path can only start at a node without predecessors and end at a node without successors
+    let no_predecessors: Vec<usize> = (0..size).filter(|&node| predecessors[node].is_empty()).collect();
+    let no_successors: Vec<usize> = (0..size).filter(|&node| successors[node].is_empty()).collect();
+    if let Some(&node) = no_predecessors.iter().find(|node| no_successors.contains(node)) {
+      panic!("No feasible tour exists : every edge from and to {} is forbidden", labels[node]);
+    }
+    if no_predecessors.len() > 1 {
+      panic!("No feasible tour exists : every edge to {} and {} is forbidden, but only one node can start the tour", labels[no_predecessors[0]], labels[no_predecessors[1]]);
+    }
+    if no_successors.len() > 1 {
+      panic!("No feasible tour exists : every edge from {} and {} is forbidden, but only one node can end the tour", labels[no_successors[0]], labels[no_successors[1]]);
+    }
+    let required_before: Vec<Vec<usize>> = (0..size).map(|node| constraints.required_before(node).to_vec()).collect();
+    let starts = if no_predecessors.is_empty() { (0..size).collect() } else { no_predecessors };
+    let starts = starts.into_iter().filter(|&node| required_before[node].is_empty()).collect();
+
+    let mut allowed_edges = Self { successors, predecessors, required_before, starts, feasible_tour: Vec::new() };
+
+    // try each possible start, the search proves that no tour exists if it explores every path
+    let mut starts = allowed_edges.starts.clone();
+    starts.shuffle(rng);
+    let mut steps = 0;
+    let mut aborted = false;
+    for start in starts {
+      match allowed_edges.search(start, rng, &mut steps, FEASIBLE_SEARCH_MAX_STEPS) {
+        SearchOutcome::Found(tour) => {
+          allowed_edges.feasible_tour = tour;
+          return Some(allowed_edges);
+        },
+        SearchOutcome::Exhausted => {},
+        SearchOutcome::Aborted => {
+          aborted = true;
+          break;
+        }
+      }
+    }
+
+    if aborted {
+      panic!("No feasible tour was found after {} steps : the dataset probably has too many forbidden edges", steps);
+    }
+    panic!("No feasible tour exists : there is no path visiting every node using only allowed edges");
+  }
+
+  // returns a random feasible tour, or the tour found while loading the dataset if none is found quickly
+  pub fn random_tour(&self, rng: &mut ThreadRng) -> Vec<usize> {
+    let start = *self.starts.choose(rng).expect("Unable to choose a start node");
+    let mut steps = 0;
+    match self.search(start, rng, &mut steps, RANDOM_SEARCH_STEPS_PER_NODE * self.successors.len()) {
+      SearchOutcome::Found(tour) => tour,
+      _ => self.feasible_tour.clone()
+    }
+  }
+
+  // depth first search of a path visiting every node from the start, using only allowed edges
+  // the next node is the one with the fewest unvisited successors (Warnsdorff's rule), ties are broken randomly
+  fn search(&self, start: usize, rng: &mut ThreadRng, steps: &mut usize, max_steps: usize) -> SearchOutcome {
+    let size = self.successors.len();
+    let mut visited = vec![false; size];
+    let mut unvisited_successors: Vec<usize> = self.successors.iter().map(|successors| successors.len()).collect();
+
+    let mut path = vec![start];
+    self.visit(start, true, &mut visited, &mut unvisited_successors);
+    let mut path_options = vec![self.options(start, &visited, &unvisited_successors, rng)];
+
+    while path.len() < size {
+      if *steps >= max_steps {
+        return SearchOutcome::Aborted;
+      }
+
+      match path_options.last_mut().expect("Unable to find the options of the last node").pop() {
+        // extend the path
+        Some(next) => {
+          *steps += 1;
+          path.push(next);
+          self.visit(next, true, &mut visited, &mut unvisited_successors);
+          path_options.push(self.options(next, &visited, &unvisited_successors, rng));
+        },
+        // no option left, backtrack
+        None => {
+          path_options.pop();
+          let node = path.pop().expect("Unable to backtrack");
+          self.visit(node, false, &mut visited, &mut unvisited_successors);
+          if path.is_empty() {
+            return SearchOutcome::Exhausted;
+          }
+        }
+      }
+    }
+
+    SearchOutcome::Found(path)
+  }
+
+  // mark a node as visited or not, which removes it from (or adds it back to) the successors of its predecessors
+  fn visit(&self, node: usize, visited_state: bool, visited: &mut [bool], unvisited_successors: &mut [usize]) {
+    visited[node] = visited_state;
+    for &predecessor in self.predecessors[node].iter() {
+      if visited_state {
+        unvisited_successors[predecessor] -= 1;
+      } else {
+        unvisited_successors[predecessor] += 1;
+      }
+    }
+  }
+
+  // returns the unvisited successors of a node that can be visited, the best one last
+  fn options(&self, node: usize, visited: &[bool], unvisited_successors: &[usize], rng: &mut ThreadRng) -> Vec<usize> {
+    let mut options: Vec<usize> = self.successors[node].iter()
+      .copied()
+      .filter(|&successor| !visited[successor] && self.required_before[successor].iter().all(|&before| visited[before]))
+      .collect();
+    options.shuffle(rng);
+    options.sort_by_key(|&successor| std::cmp::Reverse(unvisited_successors[successor]));
+    options
+  }
+}
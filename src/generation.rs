@@ -3,8 +3,12 @@ use std::fmt::Display;
 use thousands::Separable;
 
 use crate::individual::Individual;
+use crate::pareto::crowded_order;
 use crate::dataset::Dataset;
 
+// number of neighbors looked up when mutating the copies of the given tours of a first generation
+const WARM_START_NEIGHBORS_LOOKUP: usize = 4;
+
 // define the Generation struct
 pub struct Generation<'a> {
   pub id: usize,
@@ -18,7 +22,7 @@ pub struct Generation<'a> {
 // implement the Generation struct
 impl<'a> Generation<'a> {
   // function that computes the fitnesses of each individual in the population
-  fn compute_fitnesses(population: &Vec<Individual<'a>>, population_size: usize) -> Vec<f64> {
+  fn compute_fitnesses(population: &[Individual<'a>], population_size: usize) -> Vec<f64> {
     // find min and max scores
     let max = population.last().expect("Unable to get last element of population").length;
     let min = population.first().expect("Unable to get first element of population").length;
@@ -37,26 +41,81 @@ impl<'a> Generation<'a> {
       fitnesses[index] += fitnesses[index-1];
     }
     
-    // return results scaled to [0, 1]
+    // return results scaled to [0, 1], uniformly if every individual has the same length
     let length = fitnesses[population_size-1];
+    if length == 0.0 {
+      return (1..=population_size).map(|index| index as f64 / population_size as f64).collect();
+    }
     fitnesses.iter().map(|fitness| fitness/length).collect()
   }
 
+  // keep the best individuals of a population with several costs, by front then by decreasing crowding distance (NSGA-II)
+  fn crowded_selection(population: Vec<Individual<'a>>, population_size: usize) -> Vec<Individual<'a>> {
+    let objectives: Vec<Vec<f64>> = population.iter().map(Individual::objectives).collect();
+    let mut individuals: Vec<Option<Individual<'a>>> = population.into_iter().map(Some).collect();
+    crowded_order(&objectives).into_iter()
+      .take(population_size)
+      .map(|index| individuals[index].take().expect("Unable to find an individual"))
+      .collect()
+  }
+
   // returns a new instance of the Generation struct
-  pub fn new(id: usize, number_of_generations: usize, population_size: usize, dataset: &'a Dataset, rng: &mut ThreadRng) -> Self {
+  pub fn new(id: usize, number_of_generations: usize, population_size: usize, nearest_neighbor_seeds: usize, dataset: &'a Dataset, rng: &mut ThreadRng) -> Self {
     // create a new vector of solutions
     let mut population = Vec::new();
 
-    // create new random solutions
-    for _ in 0..population_size {
+    // create solutions with the nearest neighbor heuristic from random starts
+    // the ones stuck on forbidden edges are replaced by random solutions
+    for _ in 0..nearest_neighbor_seeds.min(population_size) {
+      let individual = Individual::new_nearest_neighbor(dataset, rng.gen_range(0..dataset.size));
+      if individual.length.is_finite() {
+        population.push(individual);
+      }
+    }
+
+    // complete with new random solutions
+    while population.len() < population_size {
       population.push(Individual::new(dataset, rng));
     }
 
-    // sort the solutions by their length
-    population.sort_by(|ind_1, ind_2| ind_1.partial_cmp(ind_2).expect("Unable to compare individuals while creating a new generation"));
+    Self::from_population(id, number_of_generations, population_size, dataset, population)
+  }
+
+  // returns a new instance of the Generation struct starting from the given tours (warm start)
+  // each tour is given by its nodes and the number of visited ones, the others being skipped
+  // the population is completed with mutated copies of the tours, the ones using a forbidden edge being replaced by random solutions
+  pub fn from_tours(id: usize, number_of_generations: usize, population_size: usize, dataset: &'a Dataset, tours: Vec<(Vec<usize>, usize)>, rng: &mut ThreadRng) -> Self {
+    let mut population: Vec<Individual<'a>> = tours.into_iter()
+      .take(population_size)
+      .map(|(nodes, visited)| Individual::from_visited_nodes(dataset, nodes, visited))
+      .filter(|individual| individual.length.is_finite())
+      .collect();
+
+    // each copy is mutated once, keeping the population diverse
+    let tours_count = population.len();
+    while population.len() < population_size {
+      let individual = match tours_count {
+        0 => Individual::new(dataset, rng),
+        _ => population[rng.gen_range(0..tours_count)].mutate(rng, WARM_START_NEIGHBORS_LOOKUP, 1)
+      };
+      population.push(individual);
+    }
+
+    Self::from_population(id, number_of_generations, population_size, dataset, population)
+  }
+
+  // returns a new instance of the Generation struct made of the given population
+  fn from_population(id: usize, number_of_generations: usize, population_size: usize, dataset: &'a Dataset, mut population: Vec<Individual<'a>>) -> Self {
+    // sort the solutions by their length, or by front and crowding distance with several costs
+    let fitnesses = if dataset.cost_matrices.is_empty() {
+      population.sort_by(|ind_1, ind_2| ind_1.partial_cmp(ind_2).expect("Unable to compare individuals while creating a new generation"));
+      Generation::compute_fitnesses(&population, population_size)
+    } else {
+      population = Generation::crowded_selection(population, population_size);
+      Vec::new()
+    };
 
     // create the struct
-    let fitnesses = Generation::compute_fitnesses(&population, population_size);
     Self {
       id,
       number_of_generations,
@@ -68,7 +127,13 @@ impl<'a> Generation<'a> {
   }
 
   // select a parent for crossover depending on the selection weights
+  // with several costs, the first of two random individuals in the crowded order is selected (binary tournament)
   pub fn select_parent(&self, rng: &mut ThreadRng) -> &Individual<'a> {
+    if !self.population[0].dataset.cost_matrices.is_empty() {
+      let index = rng.gen_range(0..self.population.len()).min(rng.gen_range(0..self.population.len()));
+      return &self.population[index];
+    }
+
     let pointer = rng.gen_range(0f64..1f64);
     let selected_parent_index = self.fitnesses.iter()
       .position(|&weight| pointer <= weight)
@@ -114,6 +179,13 @@ impl<'a> Generation<'a> {
       new_generation.population.push(child);
     }
 
+    // with several costs, the parents compete with their children, and the best fronts are kept
+    if !self.population[0].dataset.cost_matrices.is_empty() {
+      new_generation.population.extend(self.population.iter().cloned());
+      new_generation.population = Generation::crowded_selection(new_generation.population, new_generation.population_size);
+      return new_generation;
+    }
+
     // sort the new generation by their length
     new_generation.population.sort_by(|sol_1, sol_2| sol_1.partial_cmp(sol_2).expect("Unable to compare solutions while creating a new generation"));
 
@@ -131,13 +203,13 @@ impl<'a> Generation<'a> {
 // implement the Display trait for the Generation struct
 impl<'a> Display for Generation<'a> {
   fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-    write!(f, "┌─ GENERATION #{:0>gen_padding$} {:─>gen_padding_2$}─┐\n", self.id, "", gen_padding=self.number_of_generations_display_width, gen_padding_2=self.population[0].individual_display_width-14-self.number_of_generations_display_width)?;
+    writeln!(f, "┌─ GENERATION #{:0>gen_padding$} {:─>gen_padding_2$}─┐", self.id, "", gen_padding=self.number_of_generations_display_width, gen_padding_2=self.population[0].individual_display_width-14-self.number_of_generations_display_width)?;
 
     for index in 0..self.population_size {
-      write!(f, "│ {} │\n", self.population[index])?;
+      writeln!(f, "│ {} │", self.population[index])?;
     }
 
-    write!(f, "└─{:─>gen_padding$}─┘\n", "", gen_padding=self.population[0].individual_display_width)?;
+    writeln!(f, "└─{:─>gen_padding$}─┘", "", gen_padding=self.population[0].individual_display_width)?;
     
     Ok(())
   }
@@ -2,6 +2,7 @@ use std::fmt::Display;
 use std::cmp::{Ordering};
 use rand::prelude::*;
 use crate::dataset::{Dataset,Matrix};
+use crate::pareto::dominates;
 use crate::utils::ThousandsDisplayPolicy;
 
 // definition of the Individual struct
@@ -9,15 +10,33 @@ use crate::utils::ThousandsDisplayPolicy;
 pub struct Individual<'a>  {
   pub size: usize,
   pub nodes: Vec<usize>,
+  // number of nodes visited, only the first ones being visited in an orienteering problem, with clusters or with optional nodes
+  pub visited: usize,
   pub dataset: &'a Dataset,
   pub length: f64,
+  // cost of the visited nodes for each other cost matrix of the dataset
+  pub costs: Vec<f64>,
   pub individual_display_width: usize
 }
 
 // implement the Individual struct
 impl<'a> Individual<'a> {
   // update the length of the individual
-  fn compute_length(distance_matrix: &Matrix, nodes: &Vec<usize>, size: usize) -> f64 {
+  // the length is infinite if the individual uses a forbidden edge
+  fn compute_length(distance_matrix: &Matrix, nodes: &[usize], size: usize) -> f64 {
+    // integer distances are summed as integers, so lengths are exact
+    if distance_matrix.is_integer() {
+      let mut total_length: i64 = 0;
+      for node_index in 0..size - 1 {
+        let distance = distance_matrix.get(nodes[node_index], nodes[node_index + 1]);
+        if !distance.is_finite() {
+          return f64::INFINITY;
+        }
+        total_length += distance as i64;
+      }
+      return total_length as f64;
+    }
+
     // define a variable that will hold the total length of the individual
     let mut total_length = 0.0;
 
@@ -30,23 +49,168 @@ impl<'a> Individual<'a> {
     total_length
   }
 
+  // compute the value minimized by the genetic algorithm: the length of the individual,
+  // plus the penalty of its lateness if the dataset has time windows,
+  // or the objective of its best split into routes if there are several salesmen,
+  // or the opposite of the prize collected by its visited nodes in an orienteering problem,
+  // plus the costs of the visited nodes and the penalties of the skipped ones if the nodes have costs
+  fn evaluate(dataset: &Dataset, nodes: &[usize], visited: usize) -> f64 {
+    if let Some(orienteering) = &dataset.orienteering {
+      return orienteering.objective(&dataset.distance_matrix, &nodes[..visited]);
+    }
+    if let Some(fleet) = &dataset.fleet {
+      return fleet.split(&dataset.distance_matrix, nodes).0;
+    }
+
+    let length = Self::compute_length(&dataset.distance_matrix, &nodes[..visited], visited);
+    if let Some(node_costs) = &dataset.node_costs {
+      let (visit_cost, skip_penalty) = node_costs.totals(nodes, visited);
+      return length + visit_cost + skip_penalty;
+    }
+    match &dataset.time_windows {
+      Some(time_windows) if length.is_finite() => {
+        let (total_lateness, _) = time_windows.lateness(&dataset.distance_matrix, nodes);
+        time_windows.objective(length, total_lateness, dataset.longest_path_length)
+      },
+      _ => length
+    }
+  }
+
+  // returns the length of the visited nodes of the individual, going back to the depot in an orienteering problem
+  // unlike its objective, it leaves out the lateness, the prizes, the node costs and the split into routes
+  pub fn tour_length(&self) -> f64 {
+    match &self.dataset.orienteering {
+      Some(orienteering) => orienteering.length(&self.dataset.distance_matrix, &self.nodes[..self.visited]),
+      None => Self::compute_length(&self.dataset.distance_matrix, &self.nodes[..self.visited], self.visited)
+    }
+  }
+
+  // compute the cost of the visited nodes for each other cost matrix of the dataset
+  fn compute_costs(dataset: &Dataset, nodes: &[usize], visited: usize) -> Vec<f64> {
+    dataset.cost_matrices.iter().map(|(_, cost_matrix)| Self::compute_length(cost_matrix, &nodes[..visited], visited)).collect()
+  }
+
+  // returns the objective of the individual as displayed, with thousands separators
+  // the orienteering objective (the opposite of the prize plus a tiny part of the length breaking ties) is shown with 2 decimals
+  pub fn display_objective(&self) -> String {
+    if self.dataset.orienteering.is_some() {
+      format!("{:.2}", self.length).thousands()
+    } else {
+      self.length.thousands()
+    }
+  }
+
+  // returns the values minimized by the genetic algorithm : the length, then the other costs
+  pub fn objectives(&self) -> Vec<f64> {
+    let mut objectives = Vec::with_capacity(self.costs.len() + 1);
+    objectives.push(self.length);
+    objectives.extend_from_slice(&self.costs);
+    objectives
+  }
+
   // returns a new random instance of the individual struct
   pub fn new (dataset: &'a Dataset, rng: &mut ThreadRng) -> Self {
+    // only random tours using allowed edges are generated when some edges are forbidden
+    if let Some(allowed_edges) = &dataset.allowed_edges {
+      return Self::from_nodes(dataset, allowed_edges.random_tour(rng));
+    }
+
     // generate a list of nodes as numbers
     let mut nodes: Vec<usize> = (0..dataset.size).collect();
 
     // shuffle the order in which the nodes are visited
     nodes.shuffle(rng);
 
-    // compute the length of the individual
-    let length = Self::compute_length(&dataset.distance_matrix, &nodes, dataset.size);
+    // create the individual
+    Self::from_nodes(dataset, nodes)
+  }
+
+  // returns a new instance of the individual struct built by always going to the closest unvisited node
+  // with forbidden edges, the heuristic can get stuck and return an individual with an infinite length
+  pub fn new_nearest_neighbor (dataset: &'a Dataset, start: usize) -> Self {
+    let mut nodes: Vec<usize> = Vec::with_capacity(dataset.size);
+    let mut visited: Vec<bool> = vec![false; dataset.size];
+    let mut first_unvisited = 0;
+
+    // the spatial index is consumed as nodes are visited
+    let mut spatial_index = dataset.spatial_index.clone();
+
+    let mut last_node = start;
+    loop {
+      nodes.push(last_node);
+      visited[last_node] = true;
+      if let Some(spatial_index) = spatial_index.as_mut() {
+        spatial_index.remove(last_node);
+      }
+      if nodes.len() == dataset.size {
+        break;
+      }
+
+      last_node = match &spatial_index {
+        // query the spatial index of the remaining locations
+        Some(spatial_index) => {
+          spatial_index.nearest(spatial_index.location(last_node), |_| true).expect("Unable to find an unvisited node")
+        },
+        // use the candidate neighbors, and scan the unvisited nodes if they are all visited
+        _ => match dataset.nodes_neighbors[last_node].iter().find(|&&neighbor| !visited[neighbor]) {
+          Some(&neighbor) => neighbor,
+          None => {
+            while visited[first_unvisited] {
+              first_unvisited += 1;
+            }
+            (first_unvisited..dataset.size)
+              .filter(|&node| !visited[node])
+              .min_by(|&n1, &n2| dataset.distance_matrix.get(last_node, n1).partial_cmp(&dataset.distance_matrix.get(last_node, n2)).expect("Error while looking for the nearest node"))
+              .expect("Unable to find an unvisited node")
+          }
+        }
+      };
+    }
+
+    // create the individual
+    Self::from_nodes(dataset, nodes)
+  }
+
+  // returns a new instance of the individual struct visiting the given nodes in order
+  // the nodes are reordered to use the fixed edges, skipped until the tour fits in the budget of an orienteering problem,
+  // or reordered to only visit the representative of each cluster
+  pub fn from_nodes (dataset: &'a Dataset, nodes: Vec<usize>) -> Self {
+    Self::from_visited_nodes(dataset, nodes, dataset.size)
+  }
+
+  // returns a new instance of the individual struct visiting the first given nodes in order, the other ones being skipped
+  // nodes can only be skipped in orienteering problems and with skip penalties, clusters choosing the visited nodes themselves
+  pub fn from_visited_nodes (dataset: &'a Dataset, mut nodes: Vec<usize>, visited: usize) -> Self {
+    dataset.constraints.repair(&mut nodes);
+    let mut visited = if dataset.orienteering.is_some() || dataset.node_costs.is_some() { visited } else { dataset.size };
+    if let Some(orienteering) = &dataset.orienteering {
+      orienteering.repair(&dataset.distance_matrix, &mut nodes, &mut visited);
+    }
+    if let Some(clusters) = &dataset.clusters {
+      clusters.normalize(&mut nodes);
+      visited = clusters.count();
+    }
+    if let Some(node_costs) = &dataset.node_costs {
+      node_costs.repair(&dataset.distance_matrix, &mut nodes, &mut visited);
+    }
+    Self::from_tour(dataset, nodes, visited)
+  }
+
+  // returns a new instance of the individual struct visiting the first given nodes in order, as they are
+  // unlike the other constructors, the tour isn't repaired, so it may not satisfy the constraints of the dataset
+  pub fn from_tour (dataset: &'a Dataset, nodes: Vec<usize>, visited: usize) -> Self {
+    // compute the length and the costs of the individual
+    let length = Self::evaluate(dataset, &nodes, visited);
+    let costs = Self::compute_costs(dataset, &nodes, visited);
 
     // create the individual
     Self {
       size: dataset.size,
       nodes,
+      visited,
       dataset,
       length,
+      costs,
       individual_display_width: dataset.size * (dataset.longest_label_display_width + 4) - 1 + dataset.longest_path_display_width
     }
   }
@@ -56,8 +220,10 @@ impl<'a> Individual<'a> {
     Self {
       size: parent.size,
       nodes: vec![0; parent.size],
+      visited: parent.visited,
       dataset: parent.dataset,
       length: 0.0,
+      costs: Vec::new(),
       individual_display_width: parent.individual_display_width
     }
   }
@@ -75,7 +241,7 @@ impl<'a> Individual<'a> {
     }
 
     // create a new empty solution
-    let mut child = Self::new_empty_from_parent(&parent1);
+    let mut child = Self::new_empty_from_parent(parent1);
 
     // append a first city
     child.nodes[0] = parent1.nodes[0];
@@ -83,6 +249,7 @@ impl<'a> Individual<'a> {
     // crossover algorithm
     let mut remaining_nodes: Vec<bool> = vec![true; child.size];
     remaining_nodes[child.nodes[0]] = false;
+    let mut first_remaining = 0;
 
     for i in 1..child.size {
       let last_node = child.nodes[i-1];
@@ -90,28 +257,43 @@ impl<'a> Individual<'a> {
       let parent1_next = parent1_nodemap[last_node];
       let parent2_next = parent2_nodemap[last_node];
       
-      fn find_next<'a>(child: &mut Individual<'a>, i:usize, last_node: usize, remaining_nodes: &mut Vec<bool>) {
+      fn find_next<'a>(child: &mut Individual<'a>, i:usize, last_node: usize, remaining_nodes: &mut [bool], first_remaining: &mut usize) {
         for &potential_next_node in child.dataset.nodes_neighbors[last_node].iter() {
-          if potential_next_node != last_node {
-            if remaining_nodes[potential_next_node] {
-              remaining_nodes[potential_next_node] = false;
-              child.nodes[i] = potential_next_node;
-              return;
-            }
+          if potential_next_node != last_node && remaining_nodes[potential_next_node] {
+            remaining_nodes[potential_next_node] = false;
+            child.nodes[i] = potential_next_node;
+            return;
           }
         }
+
+        // every candidate neighbor is already used, so take the first remaining node
+        // nodes are never added back, so the cursor only moves forward
+        while !remaining_nodes[*first_remaining] {
+          *first_remaining += 1;
+        }
+
+        // with forbidden edges, prefer the first remaining node reachable from the last node
+        let matrix = &child.dataset.distance_matrix;
+        let next_node = match &child.dataset.allowed_edges {
+          Some(_) => (*first_remaining..child.size)
+            .find(|&node| remaining_nodes[node] && matrix.get(last_node, node).is_finite())
+            .unwrap_or(*first_remaining),
+          None => *first_remaining
+        };
+        remaining_nodes[next_node] = false;
+        child.nodes[i] = next_node;
       }
 
-      fn try_set_node<'a>(child: &mut Individual<'a>, i:usize, last_node: usize, target_node: usize, remaining_nodes: &mut Vec<bool>) {
+      fn try_set_node<'a>(child: &mut Individual<'a>, i:usize, last_node: usize, target_node: usize, remaining_nodes: &mut [bool], first_remaining: &mut usize) {
         if remaining_nodes[target_node] {
           remaining_nodes[target_node] = false;
           child.nodes[i] = target_node;
         } else {
-          find_next(child, i, last_node, remaining_nodes);
+          find_next(child, i, last_node, remaining_nodes, first_remaining);
         }
       }
 
-      fn try_set_node_2<'a>(child: &mut Individual<'a>, i:usize, last_node: usize, target_node_1: usize, target_node_2: usize, remaining_nodes: &mut Vec<bool>) {
+      fn try_set_node_2<'a>(child: &mut Individual<'a>, i:usize, last_node: usize, target_node_1: usize, target_node_2: usize, remaining_nodes: &mut [bool], first_remaining: &mut usize) {
         if remaining_nodes[target_node_1] {
           remaining_nodes[target_node_1] = false;
           child.nodes[i] = target_node_1;
@@ -119,26 +301,44 @@ impl<'a> Individual<'a> {
           remaining_nodes[target_node_2] = false;
           child.nodes[i] = target_node_2;
         } else {
-          find_next(child, i, last_node, remaining_nodes);
+          find_next(child, i, last_node, remaining_nodes, first_remaining);
         }
       }
 
       match (parent1_next, parent2_next) {
         (Some(p1_next), Some(p2_next)) => {
           if child.dataset.distance_matrix.get(last_node,p1_next) < child.dataset.distance_matrix.get(last_node,p2_next) {
-            try_set_node_2(&mut child, i, last_node, p1_next, p2_next, &mut remaining_nodes);
+            try_set_node_2(&mut child, i, last_node, p1_next, p2_next, &mut remaining_nodes, &mut first_remaining);
           } else {
-            try_set_node_2(&mut child, i, last_node, p2_next, p1_next, &mut remaining_nodes);
+            try_set_node_2(&mut child, i, last_node, p2_next, p1_next, &mut remaining_nodes, &mut first_remaining);
           }
         },
-        (Some(p1_next), None) => try_set_node(&mut child, i, last_node, p1_next, &mut remaining_nodes),
-        (None, Some(p2_next)) => try_set_node(&mut child, i, last_node, p2_next, &mut remaining_nodes),
-        (None, None) => find_next(&mut child, i, last_node, &mut remaining_nodes)
+        (Some(p1_next), None) => try_set_node(&mut child, i, last_node, p1_next, &mut remaining_nodes, &mut first_remaining),
+        (None, Some(p2_next)) => try_set_node(&mut child, i, last_node, p2_next, &mut remaining_nodes, &mut first_remaining),
+        (None, None) => find_next(&mut child, i, last_node, &mut remaining_nodes, &mut first_remaining)
       }
     }
 
-    // update child's length
-    child.length = Self::compute_length(&child.dataset.distance_matrix, &child.nodes, child.size);
+    // use the fixed edges, visit as many nodes as the parent visiting the most, and update child's length
+    child.dataset.constraints.repair(&mut child.nodes);
+    if let Some(orienteering) = &child.dataset.orienteering {
+      child.visited = parent1.visited.max(parent2.visited);
+      orienteering.repair(&child.dataset.distance_matrix, &mut child.nodes, &mut child.visited);
+    }
+    if let Some(clusters) = &child.dataset.clusters {
+      clusters.normalize(&mut child.nodes);
+    }
+    if let Some(node_costs) = &child.dataset.node_costs {
+      child.visited = parent1.visited.max(parent2.visited);
+      node_costs.repair(&child.dataset.distance_matrix, &mut child.nodes, &mut child.visited);
+    }
+    child.length = Self::evaluate(child.dataset, &child.nodes, child.visited);
+    child.costs = Self::compute_costs(child.dataset, &child.nodes, child.visited);
+
+    // the child uses a forbidden edge, so the best parent is kept instead
+    if !child.length.is_finite() {
+      return if parent1.length <= parent2.length { parent1.clone() } else { parent2.clone() };
+    }
 
     // return the newly created child
     child
@@ -154,25 +354,51 @@ impl<'a> Individual<'a> {
       let mut index_1 = rng.gen_range(0..child.size);
       let mut index_2 = rng.gen_range(0..child.size);
       if index_1 > index_2 {
-        let tmp = index_1;
-        index_1 = index_2;
-        index_2 = tmp;
+        std::mem::swap(&mut index_1, &mut index_2);
+      }
+      for i in index_1..(index_1 + index_2).div_ceil(2) {
+        child.nodes.swap(i, index_2 - i + index_1);
       }
-      for i in index_1..(index_1 + index_2 + 1)/2 {
-        let temp = child.nodes[i];
-        child.nodes[i] = child.nodes[index_2 - i + index_1];
-        child.nodes[index_2 - i + index_1] = temp;
+
+      // apply insertion or drop mutation in an orienteering problem
+      if let Some(orienteering) = &child.dataset.orienteering {
+        if rng.gen_range(0..2) == 0 {
+          orienteering.insert(rng, &child.dataset.distance_matrix, &mut child.nodes, &mut child.visited);
+        } else {
+          orienteering.drop(rng, &mut child.nodes, &mut child.visited);
+        }
+      }
+
+      // visit or skip an optional node
+      if let Some(node_costs) = child.dataset.node_costs.as_ref().filter(|node_costs| node_costs.has_optional_nodes()) {
+        if rng.gen_range(0..2) == 0 {
+          node_costs.insert(rng, &child.dataset.distance_matrix, &mut child.nodes, &mut child.visited);
+        } else {
+          node_costs.drop(rng, &mut child.nodes, &mut child.visited);
+        }
+      }
+
+      // change the representative of a cluster
+      if let Some(clusters) = &child.dataset.clusters {
+        clusters.normalize(&mut child.nodes);
+        clusters.switch(rng, &child.dataset.nodes_neighbors, &mut child.nodes);
       }
 
       // apply exchange mutation
       let index_1 = rng.gen_range(0..child.size);
       let node_1 = child.nodes[index_1];
-      let distance: usize = rng.gen_range(0..neighbors_distance_lookup);
-      let node_2 = child.dataset.nodes_neighbors[node_1][distance];
+      let neighbors = &child.dataset.nodes_neighbors[node_1];
+      // the node is its own closest neighbor (distance 0), in which case nothing is exchanged
+      let distance: usize = rng.gen_range(0..neighbors_distance_lookup.clamp(1, neighbors.len() + 1));
+      if distance == 0 {
+        return;
+      }
+      let node_2 = neighbors[distance - 1];
       let index_2 = child.nodes.iter().position(|&node| node == node_2).expect("node not found during mutation exchange");
 
       child.nodes[index_1] = node_2;
       child.nodes[index_2] = node_1;
+
     }
 
     for _ in 0..best_out_of {
@@ -187,16 +413,36 @@ impl<'a> Individual<'a> {
         }
       }
 
-      // update the length
-      child.length = Self::compute_length(&child.dataset.distance_matrix, &child.nodes, child.size);
-
-      // save the child if it is the best individual so far
-      if best_child.is_none() || child.length < best_child.as_ref().expect("Previous best individual not found").length {
+      // use the fixed edges, fit in the budget, update the length, and drop the child if it uses a forbidden edge
+      child.dataset.constraints.repair(&mut child.nodes);
+      if let Some(orienteering) = &child.dataset.orienteering {
+        orienteering.repair(&child.dataset.distance_matrix, &mut child.nodes, &mut child.visited);
+      }
+      if let Some(clusters) = &child.dataset.clusters {
+        clusters.normalize(&mut child.nodes);
+      }
+      if let Some(node_costs) = &child.dataset.node_costs {
+        node_costs.repair(&child.dataset.distance_matrix, &mut child.nodes, &mut child.visited);
+      }
+      child.length = Self::evaluate(child.dataset, &child.nodes, child.visited);
+      if !child.length.is_finite() {
+        continue;
+      }
+      child.costs = Self::compute_costs(child.dataset, &child.nodes, child.visited);
+
+      // save the child if it is the best individual so far, or if it dominates it with several costs
+      let better = match &best_child {
+        None => true,
+        Some(best) if child.costs.is_empty() => child.length < best.length,
+        Some(best) => dominates(&child.objectives(), &best.objectives())
+      };
+      if better {
         best_child = Some(child);
       }
     }
 
-    best_child.expect("Best individual not found")
+    // every child uses a forbidden edge, so the individual is kept as it is
+    best_child.unwrap_or_else(|| self.clone())
   }
 }
   
@@ -220,8 +466,10 @@ impl<'a> Clone for Individual<'a> {
     Self {
       size: self.size,
       nodes: self.nodes.clone(),
+      visited: self.visited,
       dataset: self.dataset,
       length: self.length,
+      costs: self.costs.clone(),
       individual_display_width: self.individual_display_width
     }
   }
@@ -233,16 +481,16 @@ impl<'a> Display for Individual<'a> {
     // create a new string that will contain the stringified individual
     let mut result = String::from("");
 
-    // for each node in the individual, add it to the string
-    for (node_index, &node) in self.nodes.iter().enumerate() {
-      result.push_str(&format!("{:>width$}", self.dataset.labels[node], width = self.dataset.longest_label_display_width));
-      if node_index < self.size - 1 {
+    // for each visited node in the individual, add it to the string
+    for (node_index, &node) in self.nodes[..self.visited].iter().enumerate() {
+      result.push_str(&format!("{:>width$}", self.dataset.labels()[node], width = self.dataset.longest_label_display_width));
+      if node_index < self.visited - 1 {
         result.push_str(" -> ");
       }
     }
 
     // add the length of the individual and a new line
-    result.push_str(&format!(" · {:>width$}", self.length.thousands(), width = self.dataset.longest_path_display_width));
+    result.push_str(&format!(" · {:>width$}", self.display_objective(), width = self.dataset.longest_path_display_width));
 
     // write the string to the formatter
     write!(f, "{}", result)
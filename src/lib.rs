@@ -0,0 +1,25 @@
+// genetic algorithm solving the traveling salesman problem and its variants, used by the tsp_solver program
+
+pub mod benchmark;
+pub mod bounds;
+pub mod candidates;
+pub mod clusters;
+pub mod constraints;
+pub mod csv;
+pub mod cvrplib;
+pub mod dataset;
+pub mod dynamic;
+pub mod exact;
+pub mod feasibility;
+pub mod individual;
+pub mod generation;
+pub mod metric;
+pub mod orienteering;
+pub mod pareto;
+pub mod penalties;
+pub mod routes;
+pub mod schedule;
+pub mod spatial;
+pub mod storage;
+pub mod tours;
+pub mod utils;
@@ -3,25 +3,80 @@ use std::fs::remove_file;
 use std::io::Write;
 use std::path::Path;
 use std::time::Instant;
-use clap::Parser;
+use clap::{Parser,Subcommand};
 
-mod dataset;
-mod individual;
-mod generation;
-mod utils;
-
-use dataset::Dataset;
-use generation::Generation;
-use utils::ThousandsDisplayPolicy;
+use tsp_solver::{benchmark,bounds,exact,tours};
+use tsp_solver::tours::TourEvaluation;
+use tsp_solver::candidates::CandidateSet;
+use tsp_solver::dataset::{Dataset,DatasetOptions,DEFAULT_CANDIDATES_COUNT};
+use tsp_solver::exact::TourKind;
+use tsp_solver::generation::Generation;
+use tsp_solver::individual::Individual;
+use tsp_solver::metric::MetricReport;
+use tsp_solver::orienteering::Visits;
+use tsp_solver::pareto::ParetoFront;
+use tsp_solver::penalties::NodeCostsReport;
+use tsp_solver::routes::{Routes,RoutesObjective};
+use tsp_solver::schedule::{Schedule,TimeWindowsMode,DEFAULT_LATENESS_PENALTY};
+use tsp_solver::storage::{CacheMode,Layout,Precision,Rounding};
+use tsp_solver::utils::ThousandsDisplayPolicy;
 
 // create a command line arguments parser
 #[derive(Parser)]
 #[clap(author, version, about)]
 struct ArgsParser {
   // dataset filename
-  #[clap(short='d', long, default_value="datasets/demo/demo.json", help="The url of the dataset (in JSON format)")]
+  #[clap(short='d', long, default_value="datasets/demo/demo.json", help="The url of the dataset (in JSON format, in the TSPLIB / CVRPLIB format with a .tsp or .vrp extension, or in the CSV format with a .csv extension)")]
   dataset_filename: String,
+
+  // csv delimiter
+  #[clap(long, default_value=",", help="The delimiter of the fields of CSV datasets, made of label,x,y lines or of a distance matrix with a header line of labels")]
+  csv_delimiter: char,
   
+  // distances precision
+  #[clap(long, value_enum, default_value="f64", help="The precision used to store the distance matrix (integer needs integer or rounded distances, locations only use it when their distances are fully cached)")]
+  precision: Precision,
+
+  // distances rounding
+  #[clap(short='r', long, value_enum, default_value="none", help="How distances are rounded to integers, following the TSPLIB rules (att needs a dataset made of locations)")]
+  rounding: Rounding,
+
+  // distances layout
+  #[clap(long, value_enum, default_value="auto", help="The layout used to store the distance matrix (auto only stores half of symmetric matrices)")]
+  layout: Layout,
+
+  // distances cache
+  #[clap(long, value_enum, default_value="auto", help="How the distances between locations are cached (auto computes the full matrix for small datasets, and the distances to the candidate neighbors for big ones)")]
+  cache: CacheMode,
+
+  // time windows mode
+  #[clap(long, value_enum, default_value="penalty", help="How late arrivals are handled on datasets with time windows (hard makes late tours always worse than the tours on time)")]
+  time_windows: TimeWindowsMode,
+
+  // lateness penalty
+  #[clap(long, default_value_t=DEFAULT_LATENESS_PENALTY, help="The cost added to the length of a tour for each time unit of lateness, with the penalty time windows mode")]
+  lateness_penalty: f64,
+
+  // number of salesmen
+  #[clap(short='m', long, default_value="1", help="The number of salesmen starting from and going back to the depot, each one visiting at least one node (1 looks for a single open path)")]
+  salesmen: usize,
+
+  // depot
+  #[clap(long, help="The label of the node the salesmen or vehicles start from (the depot of the dataset or the first node by default)")]
+  depot: Option<String>,
+
+  // routes objective
+  #[clap(long, value_enum, default_value="min-sum", help="What is minimized with several salesmen : the total length of the routes or the length of the longest one")]
+  routes_objective: RoutesObjective,
+
+  // distances check
+  #[clap(long, help="Report the violations of the triangle inequality and the asymmetry of the distances before running")]
+  check_distances: bool,
+
+  // storage benchmark
+  #[clap(long, help="Measure the memory used and the lookup throughput of each distance matrix storage on the dataset, then exit")]
+  benchmark_storage: bool,
+
   // logs filename
   #[clap(short='l', long, default_value="logs.txt", help="The url of the file to log everything to")]
   logs_filename: String,
@@ -34,10 +89,26 @@ struct ArgsParser {
   #[clap(short='p', long, default_value="100", help="The number of individuals in each generation")]
   population_size: usize,
 
+  // nearest neighbor seeds
+  #[clap(short='s', long, default_value="0", help="The number of individuals of the first generation built with the nearest neighbor heuristic instead of randomly")]
+  nearest_neighbor_seeds: usize,
+
+  // initial tour
+  #[clap(short='t', long, help="The url of a tour to improve on (a list of labels, one per line, a JSON list of labels, or a TSPLIB tour with a .tour extension) : the first generation is made of it and its mutated copies")]
+  initial_tour: Option<String>,
+
   // neighbors distance lookup
-  #[clap(short='n', long, default_value="4", help="The number of neighbors to look up : MUST NOT BE BIGGER THAN THE NUMBER OF POINTS IN THE DATASET !")]
+  #[clap(short='n', long, default_value="4", help="The number of neighbors to look up during mutation (limited by the number of candidates)")]
   neighbors_distance_lookup: usize,
 
+  // number of candidates
+  #[clap(short='k', long, default_value_t=DEFAULT_CANDIDATES_COUNT, help="The number of nearest neighbors kept for each node (lower values use less memory on big datasets)")]
+  candidates: usize,
+
+  // candidate set
+  #[clap(short='c', long, value_enum, default_value="nearest", help="How the candidate neighbors of each node are chosen (quadrant and delaunay need a dataset made of locations, alpha takes O(n^2) time and is only practical for a few thousand nodes)")]
+  candidate_set: CandidateSet,
+
   // best out of
   #[clap(short='b', long, default_value="10", help="The number of children generated during the mutation process of one individual : We only keep the best out of this number of children")]
   best_out_of: usize,
@@ -49,6 +120,34 @@ struct ArgsParser {
   // generations logging
   #[clap(short='N', long, help="Whether to log each generation")]
   no_log: bool,
+
+  // exact solver
+  #[clap(short='x', long, value_enum, help="Also compute the optimal tour of the given kind to verify the result (only usable on small datasets)")]
+  exact: Option<TourKind>,
+
+  // exact solver node limit
+  #[clap(long, default_value_t=exact::DEFAULT_NODE_LIMIT, help="The number of search nodes the exact solver explores on datasets too big for Held-Karp, before stopping with the best tour found")]
+  exact_node_limit: usize,
+
+  // lower bounds
+  #[clap(short='L', long, help="Compute lower bounds of the optimal length and display the optimality gap of the best solution")]
+  lower_bounds: bool,
+
+  // subcommand
+  #[clap(subcommand)]
+  command: Option<Command>,
+}
+
+// create the subcommands, the genetic algorithm being run without any
+#[derive(Subcommand)]
+enum Command {
+  // tour evaluation
+  #[clap(about="Evaluate a tour of the dataset instead of looking for one : display the cost of each edge, the length of the tour and the rules of the dataset it breaks")]
+  Evaluate {
+    // tour filename
+    #[clap(help="The url of the tour (a list of labels, one per line, a JSON list of labels, or a TSPLIB tour with a .tour extension)")]
+    tour_filename: String
+  },
 }
 
 // compute the factorial of a number as a float
@@ -59,13 +158,95 @@ fn ramanujan_factorial_log10(n: usize) -> usize {
   (0.0f64).max((n * (n.ln() - 1.0)) / (10.0f64).ln()).ceil() as usize
 }
 
+// display the details of a solution : its routes, the prize it collects, the costs of its nodes and its schedule, depending on the dataset
+fn display_reports(individual: &Individual) {
+  let dataset = individual.dataset;
+
+  // display each route separately
+  if let Some(fleet) = &dataset.fleet {
+    println!("{}", Routes::new(dataset, fleet, &individual.nodes));
+  }
+
+  // display the tour and the prize it collects
+  if let Some(orienteering) = &dataset.orienteering {
+    println!("{}", Visits::new(dataset, orienteering, &individual.nodes[..individual.visited]));
+  }
+
+  // display the costs of the nodes and the nodes it skips
+  if let Some(node_costs) = &dataset.node_costs {
+    println!("{}", NodeCostsReport::new(dataset, node_costs, &individual.nodes, individual.visited));
+  }
+
+  // display the arrival time at each stop
+  if let Some(time_windows) = &dataset.time_windows {
+    println!("{}", Schedule::new(dataset, time_windows, &individual.nodes));
+  }
+}
+
 // entry of the program
 fn main() {
   // parse the command line arguments
   let args = ArgsParser::parse();
 
   // load the dataset into RAM
-  let dataset = Dataset::from_file(&args.dataset_filename);
+  let dataset_options = DatasetOptions {
+    candidates_count: args.candidates,
+    candidate_set: args.candidate_set,
+    precision: args.precision,
+    layout: args.layout,
+    cache: args.cache,
+    rounding: args.rounding,
+    time_windows_mode: args.time_windows,
+    lateness_penalty: args.lateness_penalty,
+    salesmen: args.salesmen,
+    depot: args.depot,
+    routes_objective: args.routes_objective,
+    csv_delimiter: args.csv_delimiter
+  };
+  let dataset = Dataset::from_file(&args.dataset_filename, &dataset_options);
+
+  // create a random number generator
+  let mut rng = rand::thread_rng();
+
+  // only benchmark the distance matrix storages if wanted
+  if args.benchmark_storage {
+    benchmark::storage_throughput(&dataset, &mut rng);
+    return;
+  }
+
+  // report the metric properties of the distances if wanted
+  if args.check_distances {
+    println!("{}", MetricReport::new(&dataset, &mut rng));
+  }
+
+  // only evaluate the given tour if wanted, failing if it isn't valid
+  if let Some(Command::Evaluate { tour_filename }) = &args.command {
+    let evaluation = TourEvaluation::new(&dataset, &tours::read_tour_file(tour_filename));
+    println!("{}", evaluation);
+    if let Some(individual) = &evaluation.individual {
+      display_reports(individual);
+    }
+    if !evaluation.is_valid() {
+      std::process::exit(1);
+    }
+    return;
+  }
+
+  // verify that the exact solver and the lower bounds can be used before running the search
+  if args.exact.is_some() {
+    exact::verify_supported(&dataset);
+  }
+  if args.lower_bounds {
+    bounds::verify_supported(&dataset);
+  }
+
+  // read the initial tour before running the search, failing if it isn't valid
+  let initial_tour = args.initial_tour.as_ref().map(|tour_filename| {
+    tours::tour_nodes(&dataset, &tours::read_tour_file(tour_filename)).unwrap_or_else(|error| {
+      eprintln!("The initial tour isn't valid : {}", error);
+      std::process::exit(1);
+    })
+  });
 
   // log the number of valid solutions to the dataset
   println!("{}! ~= 10^{} valid solutions to the dataset", dataset.size, ramanujan_factorial_log10(dataset.size).thousands());
@@ -82,25 +263,23 @@ fn main() {
   // log the dataset
   // write!(log_file, "{}\n", dataset).expect("Unable to write to the log file");
   
-  // create a random number generator
-  let mut rng = rand::thread_rng();
-
   // start stopwatch
   let stopwatch = Instant::now();
   
-  // create a generation & log it
-  let mut generation = Generation::new(1, args.number_of_generations, args.population_size, &dataset, &mut rng);
+  // create a generation & log it, starting from the initial tour if any
+  let mut generation = match initial_tour {
+    Some(tour) => Generation::from_tours(1, args.number_of_generations, args.population_size, &dataset, vec![tour], &mut rng),
+    None => Generation::new(1, args.number_of_generations, args.population_size, args.nearest_neighbor_seeds, &dataset, &mut rng)
+  };
   if !args.no_log {
-    write!(log_file.as_ref().unwrap(), "{}\n", generation).expect("Unable to write to the log file");
+    writeln!(log_file.as_ref().unwrap(), "{}", generation).expect("Unable to write to the log file");
   }
 
   // evolve through generations
   for _ in 1..args.number_of_generations {
     generation = generation.evolve(&mut rng, args.neighbors_distance_lookup, args.best_out_of);
-    if !args.no_log {
-      if generation.id % args.display_interval == 0 {
-        write!(log_file.as_ref().unwrap(), "{}\n", generation).expect("Unable to write to the log file");
-      }
+    if !args.no_log && generation.id.is_multiple_of(args.display_interval) {
+      writeln!(log_file.as_ref().unwrap(), "{}", generation).expect("Unable to write to the log file");
     }
   }
 
@@ -108,14 +287,77 @@ fn main() {
   let execution_duration = stopwatch.elapsed();
   println!("search time : {}s\n", (execution_duration.as_millis() as f64 / 1000.0).thousands());
 
-  // display the best solution
+  // with several costs, the tours are sorted by length, so the best solution is the shortest tour of the pareto front
+  let pareto_front = if dataset.cost_matrices.is_empty() {
+    None
+  } else {
+    generation.population.sort_by(|ind_1, ind_2| ind_1.objectives().partial_cmp(&ind_2.objectives()).expect("Unable to compare individuals"));
+    Some(ParetoFront::new(&dataset, &generation.population))
+  };
+
+  // display the best solution, or the pareto front with several costs
   let mut best_solution = String::new();
   // best_solution.push_str(&format!("┌─ BEST SOLUTION {:─>gen_padding$}─┐\n", "", gen_padding=generation.population[0].individual_display_width-15).as_str());
-  best_solution.push_str(&format!("{}\n", generation.population[0]));
+  match &pareto_front {
+    Some(pareto_front) => best_solution.push_str(&format!("{}", pareto_front)),
+    None => best_solution.push_str(&format!("{}\n", generation.population[0]))
+  }
   // best_solution.push_str(&format!("└─{:─>gen_padding$}─┘\n", "", gen_padding=generation.population[0].individual_display_width));
 
   if !args.no_log {
     write!(log_file.as_ref().unwrap(), "{}", best_solution).expect("Unable to write to the log file");
   }
   println!("{}", best_solution);
+
+  // the constraints are enforced by the genetic operators, verify it on the best solution and the pareto front
+  dataset.constraints.check(dataset.labels(), &dataset.distance_matrix, &generation.population[0].nodes).expect("The best solution found doesn't satisfy the constraints");
+  for individual in pareto_front.iter().flat_map(|pareto_front| pareto_front.individuals.iter()) {
+    dataset.constraints.check(dataset.labels(), &dataset.distance_matrix, &individual.nodes).expect("A solution of the pareto front doesn't satisfy the constraints");
+  }
+
+  display_reports(&generation.population[0]);
+  if let Some(time_windows) = dataset.time_windows.as_ref().filter(|time_windows| time_windows.mode == TimeWindowsMode::Hard) {
+    let (_, late_stops) = time_windows.lateness(&dataset.distance_matrix, &generation.population[0].nodes);
+    if late_stops > 0 {
+      println!("no tour respecting every time window was found\n");
+    }
+  }
+
+  // compute the lower bounds and the optimality gap if wanted
+  if args.lower_bounds {
+    let best_length = generation.population[0].length;
+    let lower_bounds = bounds::compute(&dataset, TourKind::Open, best_length);
+    println!("1-tree lower bound     : {}", format!("{:.2}", lower_bounds.one_tree).thousands());
+    println!("Held-Karp lower bound  : {}", format!("{:.2}", lower_bounds.held_karp).thousands());
+    if let Some(assignment) = lower_bounds.assignment {
+      println!("assignment lower bound : {}", format!("{:.2}", assignment).thousands());
+    }
+    match lower_bounds.gap(best_length) {
+      Some(gap) => println!("optimality gap         : {:.2}%\n", gap),
+      None => println!("optimality gap         : undefined, the lower bounds are not positive\n")
+    }
+    if !dataset.constraints.segments.is_empty() || !dataset.constraints.precedences.is_empty() {
+      println!("the lower bounds ignore the fixed edges and the precedences : the gap can be large even for an optimal tour\n");
+    }
+  }
+
+  // compute the optimal solution if wanted
+  if let Some(kind) = args.exact {
+    let stopwatch = Instant::now();
+    let exact_solution = exact::solve(&dataset, kind, args.exact_node_limit);
+    println!("exact search time : {}s\n", (stopwatch.elapsed().as_millis() as f64 / 1000.0).thousands());
+    println!("{}\n", exact_solution);
+
+    // the genetic algorithm only looks for open tours
+    // the gap is undefined when the tour of the exact solver has a zero length
+    if kind == TourKind::Open {
+      let reference = if exact_solution.optimal { "the optimal one" } else { "the best tour of the exact solver" };
+      if exact_solution.length == 0.0 {
+        println!("the best solution found is {} longer than {}", generation.population[0].length.thousands(), reference);
+      } else {
+        let gap = (generation.population[0].length - exact_solution.length) / exact_solution.length * 100.0;
+        println!("the best solution found is {:.2}% longer than {}", gap, reference);
+      }
+    }
+  }
 }
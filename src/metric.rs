@@ -0,0 +1,188 @@
+use std::fmt::Display;
+use rand::prelude::*;
+use crate::dataset::{Dataset,Matrix};
+use crate::storage::Rounding;
+use crate::utils::ThousandsDisplayPolicy;
+
+// above this number of nodes, the triangle inequality is checked on random triples instead of every triple
+pub const TRIANGLE_CHECK_MAX_SIZE: usize = 500;
+
+// number of random triples checked on big datasets
+pub const TRIANGLE_CHECK_SAMPLES: usize = 10_000_000;
+
+// relative tolerances of the triangle inequality, a few times the precision of the stored distances
+pub const F64_TOLERANCE: f64 = 1e-9;
+pub const F32_TOLERANCE: f64 = 4.0 * f32::EPSILON as f64;
+
+// define a report on the metric properties of the distances of a dataset
+pub struct MetricReport<'a> {
+  pub dataset: &'a Dataset,
+  // unrounded euclidean distances always respect both properties, and aren't checked
+  pub euclidean: bool,
+  // triangle inequality: d(i, k) <= d(i, j) + d(j, k)
+  pub checked_triples: usize,
+  pub sampled: bool,
+  pub triangle_violations: usize,
+  // (i, j, k, excess) of the violation where d(i, k) exceeds d(i, j) + d(j, k) the most
+  pub worst_violation: Option<(usize, usize, usize, f64)>,
+  // symmetry: d(i, j) == d(j, i)
+  pub checked_pairs: usize,
+  pub asymmetric_pairs: usize,
+  pub mean_asymmetry: f64,
+  // (i, j, |d(i, j) - d(j, i)|) of the most asymmetric pair
+  pub worst_asymmetry: Option<(usize, usize, f64)>,
+  // relative tolerance of the triangle inequality, depending on the precision of the stored distances
+  tolerance: f64
+}
+
+impl<'a> MetricReport<'a> {
+  // check the triangle inequality and the symmetry of the distances
+  pub fn new(dataset: &'a Dataset, rng: &mut ThreadRng) -> Self {
+    // the spatial index is only built for datasets made of locations, whatever the cache of their distances
+    let euclidean = dataset.spatial_index.is_some() && dataset.rounding == Rounding::None;
+    let mut report = Self {
+      dataset,
+      euclidean,
+      checked_triples: 0,
+      sampled: false,
+      triangle_violations: 0,
+      worst_violation: None,
+      checked_pairs: 0,
+      asymmetric_pairs: 0,
+      mean_asymmetry: 0.0,
+      worst_asymmetry: None,
+      tolerance: match &dataset.distance_matrix {
+        Matrix::CompactDistances(_) => F32_TOLERANCE,
+        _ => F64_TOLERANCE
+      }
+    };
+
+    if euclidean {
+      return report;
+    }
+
+    report.check_triangle_inequality(rng);
+    report.check_symmetry();
+    report
+  }
+
+  // check a triple of nodes, triples with a forbidden edge are skipped
+  fn check_triple(&mut self, i: usize, j: usize, k: usize) {
+    let matrix = &self.dataset.distance_matrix;
+    if !matrix.get(i, k).is_finite() || !matrix.get(i, j).is_finite() || !matrix.get(j, k).is_finite() {
+      return;
+    }
+    let excess = matrix.get(i, k) - (matrix.get(i, j) + matrix.get(j, k));
+    self.checked_triples += 1;
+
+    // a tiny tolerance avoids reporting the rounding errors of the storage
+    if excess > self.tolerance * matrix.get(i, k).abs() {
+      self.triangle_violations += 1;
+      if self.worst_violation.is_none_or(|(_, _, _, worst)| excess > worst) {
+        self.worst_violation = Some((i, j, k, excess));
+      }
+    }
+  }
+
+  // check every triple on small datasets, random triples on big ones
+  fn check_triangle_inequality(&mut self, rng: &mut ThreadRng) {
+    let size = self.dataset.size;
+
+    if size <= TRIANGLE_CHECK_MAX_SIZE {
+      for i in 0..size {
+        for j in (0..size).filter(|&j| j != i) {
+          for k in (0..size).filter(|&k| k != i && k != j) {
+            self.check_triple(i, j, k);
+          }
+        }
+      }
+    } else {
+      self.sampled = true;
+      for _ in 0..TRIANGLE_CHECK_SAMPLES {
+        let (i, j, k) = (rng.gen_range(0..size), rng.gen_range(0..size), rng.gen_range(0..size));
+        if i != j && j != k && i != k {
+          self.check_triple(i, j, k);
+        }
+      }
+    }
+  }
+
+  // compare the distances of each pair in both directions, pairs with a forbidden edge are skipped
+  fn check_symmetry(&mut self) {
+    let matrix = &self.dataset.distance_matrix;
+    let mut total_asymmetry = 0.0;
+
+    for i in 0..self.dataset.size {
+      for j in 0..i {
+        if !matrix.get(i, j).is_finite() || !matrix.get(j, i).is_finite() {
+          continue;
+        }
+        let asymmetry = (matrix.get(i, j) - matrix.get(j, i)).abs();
+        self.checked_pairs += 1;
+        if asymmetry > 0.0 {
+          self.asymmetric_pairs += 1;
+          total_asymmetry += asymmetry;
+          if self.worst_asymmetry.is_none_or(|(_, _, worst)| asymmetry > worst) {
+            self.worst_asymmetry = Some((i, j, asymmetry));
+          }
+        }
+      }
+    }
+
+    if self.asymmetric_pairs > 0 {
+      self.mean_asymmetry = total_asymmetry / self.asymmetric_pairs as f64;
+    }
+  }
+}
+
+// implement the Display trait for the MetricReport struct
+impl<'a> Display for MetricReport<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let labels = self.dataset.labels();
+
+    if self.euclidean {
+      return writeln!(f, "euclidean distances : the triangle inequality holds and distances are symmetric");
+    }
+
+    // triangle inequality, datasets with less than 3 nodes or only forbidden edges having no triple to check
+    if self.checked_triples == 0 {
+      writeln!(f, "triangle inequality : not enough data, no triple of nodes to check")?;
+    } else {
+      writeln!(
+        f,
+        "triangle inequality : {} violations out of {} {}triples",
+        self.triangle_violations.thousands(),
+        self.checked_triples.thousands(),
+        if self.sampled { "random " } else { "" }
+      )?;
+    }
+    if let Some((i, j, k, excess)) = self.worst_violation {
+      writeln!(
+        f,
+        "  worst : {} -> {} ({}) is longer than {} -> {} -> {} ({}) by {}",
+        labels[i], labels[k], self.dataset.distance_matrix.get(i, k).thousands(),
+        labels[i], labels[j], labels[k], (self.dataset.distance_matrix.get(i, j) + self.dataset.distance_matrix.get(j, k)).thousands(),
+        excess.thousands()
+      )?;
+    }
+
+    // symmetry
+    if self.checked_pairs == 0 {
+      writeln!(f, "asymmetry : not enough data, no pair of nodes to check")?;
+    } else {
+      writeln!(
+        f,
+        "asymmetry : {} asymmetric pairs out of {} ({:.2}%)",
+        self.asymmetric_pairs.thousands(),
+        self.checked_pairs.thousands(),
+        self.asymmetric_pairs as f64 / self.checked_pairs as f64 * 100.0
+      )?;
+    }
+    if let Some((i, j, asymmetry)) = self.worst_asymmetry {
+      writeln!(f, "  mean difference between directions : {}", format!("{:.2}", self.mean_asymmetry).thousands())?;
+      writeln!(f, "  worst : {} <-> {} differs by {}", labels[i], labels[j], asymmetry.thousands())?;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,141 @@
+use std::fmt::Display;
+use rand::prelude::*;
+use crate::dataset::{Dataset,Labels,Matrix};
+use crate::utils::ThousandsDisplayPolicy;
+
+// weight of the length of a tour in its objective, relative to the smallest prize per unit of budget
+// so that the shortest tour wins between tours collecting the same prize
+const LENGTH_TIE_BREAK: f64 = 1e-6;
+
+// define an orienteering problem : the tour starts from and goes back to the depot, its length can't exceed the budget,
+// and the nodes it visits are chosen to collect the highest total prize
+// individuals only visit a prefix of their nodes, starting with the depot, the other nodes being skipped
+pub struct Orienteering {
+  prizes: Vec<f64>,
+  pub budget: f64,
+  pub depot: usize,
+  length_weight: f64
+}
+
+impl Orienteering {
+  pub fn new(labels: &Labels, depot: usize, prizes: Vec<f64>, budget: f64) -> Self {
+    if prizes.len() != labels.len() {
+      panic!("There should be one prize per node, found {} prizes for {} nodes", prizes.len(), labels.len());
+    }
+    for (node, &prize) in prizes.iter().enumerate() {
+      if !prize.is_finite() || prize < 0.0 {
+        panic!("The prize of {} should be a positive number, found {}", labels[node], prize);
+      }
+    }
+    if !budget.is_finite() || budget <= 0.0 {
+      panic!("The budget should be a positive number, found {}", budget);
+    }
+
+    // without prizes, the shortest tour is the best one
+    let smallest_prize = prizes.iter().copied().filter(|&prize| prize > 0.0).fold(f64::INFINITY, f64::min);
+    let length_weight = if smallest_prize.is_finite() { LENGTH_TIE_BREAK * smallest_prize / budget } else { 1.0 };
+    Self { prizes, budget, depot, length_weight }
+  }
+
+  // returns the length of the tour going back to the depot after the visited nodes
+  pub fn length(&self, distance_matrix: &Matrix, visited_nodes: &[usize]) -> f64 {
+    let length: f64 = visited_nodes.windows(2).map(|edge| distance_matrix.get(edge[0], edge[1])).sum();
+    length + distance_matrix.get(visited_nodes[visited_nodes.len() - 1], self.depot)
+  }
+
+  // returns the total prize collected by the visited nodes
+  pub fn prize(&self, visited_nodes: &[usize]) -> f64 {
+    visited_nodes.iter().map(|&node| self.prizes[node]).sum()
+  }
+
+  // returns the value minimized by the genetic algorithm : the opposite of the prize, the length only breaking ties
+  pub fn objective(&self, distance_matrix: &Matrix, visited_nodes: &[usize]) -> f64 {
+    self.length(distance_matrix, visited_nodes) * self.length_weight - self.prize(visited_nodes)
+  }
+
+  // move the depot to the front of the nodes, adding it to the visited ones if it was skipped
+  fn pin_depot(&self, nodes: &mut [usize], visited: &mut usize) {
+    let position = nodes.iter().position(|&node| node == self.depot).expect("Unable to find the depot");
+    if position >= *visited {
+      *visited += 1;
+    }
+    nodes[..=position].rotate_right(1);
+  }
+
+  // skip visited nodes until the tour fits in the budget, the ones with the smallest prize per unit of saved length first
+  pub fn repair(&self, distance_matrix: &Matrix, nodes: &mut [usize], visited: &mut usize) {
+    self.pin_depot(nodes, visited);
+    while *visited > 1 && self.length(distance_matrix, &nodes[..*visited]) > self.budget {
+      let ratio = |index: usize| {
+        let (previous, node) = (nodes[index - 1], nodes[index]);
+        let next = if index + 1 < *visited { nodes[index + 1] } else { self.depot };
+        let saving = distance_matrix.get(previous, node) + distance_matrix.get(node, next) - distance_matrix.get(previous, next);
+        if saving > 0.0 { self.prizes[node] / saving } else { f64::INFINITY }
+      };
+      let index = (1..*visited)
+        .min_by(|&index1, &index2| ratio(index1).partial_cmp(&ratio(index2)).expect("Error while comparing visited nodes"))
+        .expect("Unable to find a visited node");
+      nodes[index..*visited].rotate_left(1);
+      *visited -= 1;
+    }
+  }
+
+  // visit a random skipped node, at the position lengthening the tour the least
+  pub fn insert(&self, rng: &mut ThreadRng, distance_matrix: &Matrix, nodes: &mut [usize], visited: &mut usize) {
+    if *visited == nodes.len() {
+      return;
+    }
+    nodes.swap(rng.gen_range(*visited..nodes.len()), *visited);
+    let node = nodes[*visited];
+    let cost = |position: usize| {
+      let (previous, next) = (nodes[position - 1], if position < *visited { nodes[position] } else { self.depot });
+      distance_matrix.get(previous, node) + distance_matrix.get(node, next) - distance_matrix.get(previous, next)
+    };
+    let position = (1..=*visited)
+      .min_by(|&position1, &position2| cost(position1).partial_cmp(&cost(position2)).expect("Error while comparing insertion positions"))
+      .expect("Unable to find an insertion position");
+    nodes[position..=*visited].rotate_right(1);
+    *visited += 1;
+  }
+
+  // skip a random visited node, the depot excepted
+  pub fn drop(&self, rng: &mut ThreadRng, nodes: &mut [usize], visited: &mut usize) {
+    if *visited <= 1 {
+      return;
+    }
+    let index = rng.gen_range(1..*visited);
+    nodes[index..*visited].rotate_left(1);
+    *visited -= 1;
+  }
+}
+
+// define the tour of a solution to an orienteering problem, to display what it collects
+pub struct Visits<'a> {
+  pub dataset: &'a Dataset,
+  pub visited_nodes: Vec<usize>,
+  pub length: f64,
+  pub prize: f64
+}
+
+impl<'a> Visits<'a> {
+  pub fn new(dataset: &'a Dataset, orienteering: &Orienteering, visited_nodes: &[usize]) -> Self {
+    Self {
+      dataset,
+      visited_nodes: visited_nodes.to_vec(),
+      length: orienteering.length(&dataset.distance_matrix, visited_nodes),
+      prize: orienteering.prize(visited_nodes)
+    }
+  }
+}
+
+// implement the Display trait for the Visits struct
+impl<'a> Display for Visits<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let orienteering = self.dataset.orienteering.as_ref().expect("Unable to find the orienteering problem of the dataset");
+    let stops = self.visited_nodes.iter().map(|&node| self.dataset.labels()[node].as_str()).collect::<Vec<&str>>().join(" -> ");
+    let total_prize: f64 = orienteering.prizes.iter().sum();
+
+    writeln!(f, "tour · {} -> {} · length {} / budget {}", stops, self.dataset.labels()[orienteering.depot], self.length.thousands(), orienteering.budget.thousands())?;
+    writeln!(f, "prize : {} / {} · visited nodes : {} / {}", self.prize.thousands(), total_prize.thousands(), self.visited_nodes.len().thousands(), self.dataset.size.thousands())
+  }
+}
@@ -0,0 +1,115 @@
+use std::fmt::Display;
+use crate::dataset::Dataset;
+use crate::individual::Individual;
+use crate::utils::ThousandsDisplayPolicy;
+
+// returns whether the first objectives are all lower than or equal to the second ones, one of them being strictly lower
+pub fn dominates(objectives1: &[f64], objectives2: &[f64]) -> bool {
+  objectives1.iter().zip(objectives2.iter()).all(|(value1, value2)| value1 <= value2)
+    && objectives1.iter().zip(objectives2.iter()).any(|(value1, value2)| value1 < value2)
+}
+
+// sort the individuals into fronts of non-dominated objectives (fast non-dominated sort of NSGA-II)
+// returns the indexes of the individuals of each front, the best front first
+pub fn non_dominated_sort(objectives: &[Vec<f64>]) -> Vec<Vec<usize>> {
+  let count = objectives.len();
+  let mut dominated: Vec<Vec<usize>> = vec![Vec::new(); count];
+  let mut dominating_counts = vec![0; count];
+  for index1 in 0..count {
+    for index2 in index1 + 1..count {
+      if dominates(&objectives[index1], &objectives[index2]) {
+        dominated[index1].push(index2);
+        dominating_counts[index2] += 1;
+      } else if dominates(&objectives[index2], &objectives[index1]) {
+        dominated[index2].push(index1);
+        dominating_counts[index1] += 1;
+      }
+    }
+  }
+
+  // each front is made of the individuals only dominated by the previous fronts
+  let mut fronts: Vec<Vec<usize>> = Vec::new();
+  let mut front: Vec<usize> = (0..count).filter(|&index| dominating_counts[index] == 0).collect();
+  while !front.is_empty() {
+    let mut next_front = Vec::new();
+    for &index in front.iter() {
+      for &dominated_index in dominated[index].iter() {
+        dominating_counts[dominated_index] -= 1;
+        if dominating_counts[dominated_index] == 0 {
+          next_front.push(dominated_index);
+        }
+      }
+    }
+    fronts.push(front);
+    front = next_front;
+  }
+  fronts
+}
+
+// returns the crowding distance of each individual of a front : the sum over the objectives of the normalized distance between its neighbors,
+// infinite for the individuals at the ends of the front
+pub fn crowding_distances(objectives: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+  let mut distances = vec![0.0; front.len()];
+  let objectives_count = objectives.first().map_or(0, Vec::len);
+  let columns: Vec<Vec<f64>> = (0..objectives_count).map(|objective| front.iter().map(|&index| objectives[index][objective]).collect()).collect();
+  let mut order: Vec<usize> = (0..front.len()).collect();
+  for column in columns.iter() {
+    let value = |position: usize| column[position];
+    order.sort_by(|&position1, &position2| value(position1).partial_cmp(&value(position2)).expect("Error while comparing objectives"));
+
+    let (first, last) = (order[0], order[order.len() - 1]);
+    distances[first] = f64::INFINITY;
+    distances[last] = f64::INFINITY;
+    let range = value(last) - value(first);
+    if range > 0.0 {
+      for window in order.windows(3) {
+        distances[window[1]] += (value(window[2]) - value(window[0])) / range;
+      }
+    }
+  }
+  distances
+}
+
+// returns the indexes of the individuals ordered by front, then by decreasing crowding distance (crowded comparison of NSGA-II)
+pub fn crowded_order(objectives: &[Vec<f64>]) -> Vec<usize> {
+  let mut order = Vec::with_capacity(objectives.len());
+  for front in non_dominated_sort(objectives) {
+    let distances = crowding_distances(objectives, &front);
+    let mut positions: Vec<usize> = (0..front.len()).collect();
+    positions.sort_by(|&position1, &position2| distances[position2].partial_cmp(&distances[position1]).expect("Error while comparing crowding distances"));
+    order.extend(positions.into_iter().map(|position| front[position]));
+  }
+  order
+}
+
+// define the tours of the first front of a population, none of them being better than another one on every cost
+pub struct ParetoFront<'a> {
+  pub dataset: &'a Dataset,
+  pub individuals: Vec<Individual<'a>>
+}
+
+impl<'a> ParetoFront<'a> {
+  // keep the non-dominated individuals of a population, once per distinct objectives, by increasing length
+  pub fn new(dataset: &'a Dataset, population: &[Individual<'a>]) -> Self {
+    let objectives: Vec<Vec<f64>> = population.iter().map(Individual::objectives).collect();
+    let mut indexes = non_dominated_sort(&objectives).into_iter().next().unwrap_or_default();
+    indexes.sort_by(|&index1, &index2| objectives[index1].partial_cmp(&objectives[index2]).expect("Error while comparing objectives"));
+    indexes.dedup_by(|index1, index2| objectives[*index1] == objectives[*index2]);
+    Self { dataset, individuals: indexes.into_iter().map(|index| population[index].clone()).collect() }
+  }
+}
+
+// implement the Display trait for the ParetoFront struct
+impl<'a> Display for ParetoFront<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "pareto front : {} tours", self.individuals.len().thousands())?;
+    for individual in self.individuals.iter() {
+      write!(f, "{}", individual)?;
+      for ((name, _), cost) in self.dataset.cost_matrices.iter().zip(individual.costs.iter()) {
+        write!(f, " · {} {}", name, cost.thousands())?;
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
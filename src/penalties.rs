@@ -0,0 +1,143 @@
+use std::fmt::Display;
+use rand::prelude::*;
+use crate::dataset::{Dataset,Labels,Matrix};
+use crate::utils::ThousandsDisplayPolicy;
+
+// define the costs of the nodes of a dataset : each visited node costs its handling cost,
+// and optional nodes can be skipped, paying their skip penalty instead
+// individuals only visit a prefix of their nodes, the skipped nodes being the other ones
+pub struct NodeCosts {
+  costs: Vec<f64>,
+  // None for the nodes that must be visited
+  skip_penalties: Vec<Option<f64>>,
+  optional_nodes: bool
+}
+
+impl NodeCosts {
+  // build the costs of the nodes of a dataset, returns None if there are neither node costs nor skip penalties
+  // empty lists mean no node costs or no optional nodes
+  pub fn new(labels: &Labels, costs: Vec<f64>, skip_penalties: Vec<Option<f64>>) -> Option<Self> {
+    if costs.is_empty() && skip_penalties.is_empty() {
+      return None;
+    }
+    let costs = if costs.is_empty() { vec![0.0; labels.len()] } else { costs };
+    let skip_penalties = if skip_penalties.is_empty() { vec![None; labels.len()] } else { skip_penalties };
+
+    // there should be one cost and one skip penalty per node
+    if costs.len() != labels.len() {
+      panic!("There should be one cost per node, found {} costs for {} nodes", costs.len(), labels.len());
+    }
+    if skip_penalties.len() != labels.len() {
+      panic!("There should be one skip penalty per node (or null), found {} skip penalties for {} nodes", skip_penalties.len(), labels.len());
+    }
+
+    for (node, &cost) in costs.iter().enumerate() {
+      if !cost.is_finite() || cost < 0.0 {
+        panic!("The cost of {} should be a positive number, found {}", labels[node], cost);
+      }
+    }
+    for (node, skip_penalty) in skip_penalties.iter().enumerate() {
+      if let Some(skip_penalty) = skip_penalty.filter(|skip_penalty| !skip_penalty.is_finite() || *skip_penalty < 0.0) {
+        panic!("The skip penalty of {} should be a positive number, found {}", labels[node], skip_penalty);
+      }
+    }
+
+    let optional_nodes = skip_penalties.iter().any(Option::is_some);
+    Some(Self { costs, skip_penalties, optional_nodes })
+  }
+
+  // whether some nodes can be skipped
+  pub fn has_optional_nodes(&self) -> bool {
+    self.optional_nodes
+  }
+
+  // returns the nodes that must be visited but are skipped
+  pub fn skipped_mandatory_nodes(&self, nodes: &[usize], visited: usize) -> Vec<usize> {
+    nodes[visited..].iter().copied().filter(|&node| self.skip_penalties[node].is_none()).collect()
+  }
+
+  // returns the total cost of the visited nodes and the total penalty of the skipped nodes
+  // skipping a node that must be visited has an infinite penalty
+  pub fn totals(&self, nodes: &[usize], visited: usize) -> (f64, f64) {
+    let visit_cost = nodes[..visited].iter().map(|&node| self.costs[node]).sum();
+    let skip_penalty = nodes[visited..].iter().map(|&node| self.skip_penalties[node].unwrap_or(f64::INFINITY)).sum();
+    (visit_cost, skip_penalty)
+  }
+
+  // move the node after the visited ones into the visited ones, at the position lengthening the path the least
+  fn visit(distance_matrix: &Matrix, nodes: &mut [usize], visited: &mut usize) {
+    let node = nodes[*visited];
+    let cost = |position: usize| {
+      let previous = if position > 0 { Some(nodes[position - 1]) } else { None };
+      let next = if position < *visited { Some(nodes[position]) } else { None };
+      match (previous, next) {
+        (Some(previous), Some(next)) => distance_matrix.get(previous, node) + distance_matrix.get(node, next) - distance_matrix.get(previous, next),
+        (Some(previous), None) => distance_matrix.get(previous, node),
+        (None, Some(next)) => distance_matrix.get(node, next),
+        (None, None) => 0.0
+      }
+    };
+    let position = (0..=*visited)
+      .min_by(|&position1, &position2| cost(position1).partial_cmp(&cost(position2)).expect("Error while comparing insertion positions"))
+      .expect("Unable to find an insertion position");
+    nodes[position..=*visited].rotate_right(1);
+    *visited += 1;
+  }
+
+  // visit every node that can't be skipped, and at least one node
+  pub fn repair(&self, distance_matrix: &Matrix, nodes: &mut [usize], visited: &mut usize) {
+    for index in *visited..nodes.len() {
+      if self.skip_penalties[nodes[index]].is_none() || *visited == 0 {
+        nodes.swap(index, *visited);
+        Self::visit(distance_matrix, nodes, visited);
+      }
+    }
+  }
+
+  // visit a random skipped node
+  pub fn insert(&self, rng: &mut ThreadRng, distance_matrix: &Matrix, nodes: &mut [usize], visited: &mut usize) {
+    if *visited == nodes.len() {
+      return;
+    }
+    nodes.swap(rng.gen_range(*visited..nodes.len()), *visited);
+    Self::visit(distance_matrix, nodes, visited);
+  }
+
+  // skip a random optional visited node
+  pub fn drop(&self, rng: &mut ThreadRng, nodes: &mut [usize], visited: &mut usize) {
+    let optional: Vec<usize> = (0..*visited).filter(|&index| self.skip_penalties[nodes[index]].is_some()).collect();
+    if *visited <= 1 || optional.is_empty() {
+      return;
+    }
+    let index = *optional.choose(rng).expect("Unable to choose an optional node");
+    nodes[index..*visited].rotate_left(1);
+    *visited -= 1;
+  }
+}
+
+// define the costs of the nodes of a solution, to display them with the skipped nodes
+pub struct NodeCostsReport<'a> {
+  pub dataset: &'a Dataset,
+  pub skipped_nodes: Vec<usize>,
+  pub visit_cost: f64,
+  pub skip_penalty: f64
+}
+
+impl<'a> NodeCostsReport<'a> {
+  pub fn new(dataset: &'a Dataset, node_costs: &NodeCosts, nodes: &[usize], visited: usize) -> Self {
+    let (visit_cost, skip_penalty) = node_costs.totals(nodes, visited);
+    Self { dataset, skipped_nodes: nodes[visited..].to_vec(), visit_cost, skip_penalty }
+  }
+}
+
+// implement the Display trait for the NodeCostsReport struct
+impl<'a> Display for NodeCostsReport<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "visit costs : {}", self.visit_cost.thousands())?;
+    if self.skipped_nodes.is_empty() {
+      return writeln!(f, "no node is skipped");
+    }
+    let skipped = self.skipped_nodes.iter().map(|&node| self.dataset.labels()[node].as_str()).collect::<Vec<&str>>().join(", ");
+    writeln!(f, "skipped nodes : {} · skip penalties : {}", skipped, self.skip_penalty.thousands())
+  }
+}
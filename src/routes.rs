@@ -0,0 +1,245 @@
+use std::fmt::Display;
+use clap::ValueEnum;
+use crate::dataset::{Dataset,Labels,Matrix};
+use crate::utils::ThousandsDisplayPolicy;
+
+// number of steps of the binary search on the length of the longest route, enough to reach the precision of f64
+const SPLIT_SEARCH_STEPS: usize = 100;
+
+// define what is minimized when the nodes are split across several routes
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RoutesObjective {
+  // the total length of the routes
+  MinSum,
+  // the length of the longest route
+  MinMax
+}
+
+// define the vehicles leaving the depot
+pub enum Vehicles {
+  // a fixed number of salesmen, each one visiting at least one node
+  Salesmen(usize),
+  // as many vehicles as needed, the total demand of the nodes of each route not exceeding the capacity (CVRP)
+  Capacitated { capacity: f64, demands: Vec<f64> }
+}
+
+// define a fleet of vehicles starting from and going back to the same depot
+// individuals are giant tours (permutations of every node) split optimally into routes,
+// the position of the depot in the giant tour being ignored
+pub struct Fleet {
+  pub depot: usize,
+  pub vehicles: Vehicles,
+  pub objective: RoutesObjective
+}
+
+impl Fleet {
+  pub fn new(depot: usize, salesmen: usize, objective: RoutesObjective, size: usize) -> Self {
+    if salesmen == 0 {
+      panic!("There should be at least 1 salesman");
+    }
+    if salesmen > size - 1 {
+      panic!("There are more salesmen ({}) than nodes to visit ({})", salesmen, size - 1);
+    }
+    Self { depot, vehicles: Vehicles::Salesmen(salesmen), objective }
+  }
+
+  // build a fleet of vehicles with the given capacity, the demand of the depot being ignored
+  // the number of vehicles only depends on the demands, and their total length is minimized
+  pub fn capacitated(labels: &Labels, depot: usize, capacity: f64, demands: Vec<f64>) -> Self {
+    if !capacity.is_finite() || capacity <= 0.0 {
+      panic!("The capacity of the vehicles should be a positive number, found {}", capacity);
+    }
+    if demands.len() != labels.len() {
+      panic!("There should be one demand per node, found {} demands for {} nodes", demands.len(), labels.len());
+    }
+    for (node, &demand) in demands.iter().enumerate().filter(|&(node, _)| node != depot) {
+      if !demand.is_finite() || demand < 0.0 {
+        panic!("The demand of {} should be a positive number, found {}", labels[node], demand);
+      }
+      if demand > capacity {
+        panic!("The demand of {} ({}) exceeds the capacity of the vehicles ({})", labels[node], demand, capacity);
+      }
+    }
+    Self { depot, vehicles: Vehicles::Capacitated { capacity, demands }, objective: RoutesObjective::MinSum }
+  }
+
+  // returns the total demand of the nodes of a route, if the vehicles have a capacity
+  pub fn load(&self, route: &[usize]) -> Option<f64> {
+    match &self.vehicles {
+      Vehicles::Salesmen(_) => None,
+      Vehicles::Capacitated { demands, .. } => Some(route.iter().map(|&node| demands[node]).sum())
+    }
+  }
+
+  // split the giant tour into routes
+  // returns the objective value, the customers (the giant tour without the depot) and the index of the first customer of each route
+  pub fn split(&self, distance_matrix: &Matrix, nodes: &[usize]) -> (f64, Vec<usize>, Vec<usize>) {
+    let customers: Vec<usize> = nodes.iter().copied().filter(|&node| node != self.depot).collect();
+    let count = customers.len();
+    let from_depot: Vec<f64> = customers.iter().map(|&node| distance_matrix.get(self.depot, node)).collect();
+    let to_depot: Vec<f64> = customers.iter().map(|&node| distance_matrix.get(node, self.depot)).collect();
+    let edges: Vec<f64> = customers.windows(2).map(|edge| distance_matrix.get(edge[0], edge[1])).collect();
+
+    let (value, mut starts) = match (&self.vehicles, self.objective) {
+      // the best split of the giant tour into routes respecting the capacity (Prins' split) :
+      // each value is the total length of the routes serving the first visited customers, in O(n * customers per route)
+      (Vehicles::Capacitated { capacity, demands }, _) => {
+        let mut values = vec![f64::INFINITY; count + 1];
+        let mut parents = vec![0; count + 1];
+        values[0] = 0.0;
+        for first in 0..count {
+          let (mut load, mut length) = (0.0, from_depot[first]);
+          for last in first..count {
+            load += demands[customers[last]];
+            if load > *capacity {
+              break;
+            }
+            if last > first {
+              length += edges[last - 1];
+            }
+            let value = values[first] + length + to_depot[last];
+            if value < values[last + 1] {
+              values[last + 1] = value;
+              parents[last + 1] = first;
+            }
+          }
+        }
+
+        // walk back the parents to find the first customer of each route
+        let mut starts = Vec::new();
+        let mut visited = parents[count];
+        while visited > 0 {
+          starts.push(visited);
+          visited = parents[visited];
+        }
+        (values[count], starts)
+      },
+      // cutting the giant tour after a customer replaces the edge to the next customer by a return to the depot and a new departure,
+      // each cut changing the total length independently of the others, so the cheapest cuts are the best ones, in O(n)
+      (&Vehicles::Salesmen(salesmen), RoutesObjective::MinSum) => {
+        let cut_cost = |index: usize| to_depot[index] + from_depot[index + 1] - edges[index];
+        let mut cuts: Vec<usize> = (0..count - 1).collect();
+        if salesmen > 1 {
+          cuts.select_nth_unstable_by(salesmen - 2, |&c1, &c2| cut_cost(c1).partial_cmp(&cut_cost(c2)).expect("Error while comparing cuts"));
+        }
+        cuts.truncate(salesmen - 1);
+        let value = from_depot[0] + edges.iter().sum::<f64>() + to_depot[count - 1] + cuts.iter().map(|&cut| cut_cost(cut)).sum::<f64>();
+        (value, cuts.into_iter().map(|cut| cut + 1).collect::<Vec<usize>>())
+      },
+      // the longest route is minimized by a binary search on its length : the greedy split filling each route up to the bound
+      // uses the fewest routes, and the bound is lowered while it needs at most as many routes as salesmen, in O(n) per step
+      // with distances respecting the triangle inequality, longer routes are never shorter, so this split is optimal
+      (&Vehicles::Salesmen(salesmen), RoutesObjective::MinMax) => {
+        let mut prefix_lengths = vec![0.0; count];
+        for index in 1..count {
+          prefix_lengths[index] = prefix_lengths[index - 1] + edges[index - 1];
+        }
+        let route_length = |first: usize, last: usize| from_depot[first] + prefix_lengths[last] - prefix_lengths[first] + to_depot[last];
+
+        // returns the first customer of each route but the first one, or None if more routes than salesmen are needed
+        let greedy_split = |bound: f64| {
+          let mut starts = Vec::with_capacity(salesmen);
+          let mut first = 0;
+          if route_length(0, 0) > bound {
+            return None;
+          }
+          for last in 1..count {
+            if route_length(first, last) > bound {
+              if route_length(last, last) > bound || starts.len() + 1 == salesmen {
+                return None;
+              }
+              starts.push(last);
+              first = last;
+            }
+          }
+          Some(starts)
+        };
+
+        // no route is longer than the longest departure, the whole path and the longest return
+        let longest = |distances: &[f64]| distances.iter().copied().fold(0.0, f64::max);
+        let (mut low, mut high) = (0.0, longest(&from_depot) + prefix_lengths[count - 1] + longest(&to_depot));
+        for _ in 0..SPLIT_SEARCH_STEPS {
+          let middle = (low + high) / 2.0;
+          if middle <= low || middle >= high {
+            break;
+          }
+          if greedy_split(middle).is_some() {
+            high = middle;
+          } else {
+            low = middle;
+          }
+        }
+
+        // each salesman visits at least one customer, cutting a route in two never makes it longer with the triangle inequality
+        let mut starts = greedy_split(high).expect("Unable to split the giant tour under the bound");
+        let mut is_start = vec![false; count];
+        for &start in starts.iter() {
+          is_start[start] = true;
+        }
+        for (cut, _) in is_start.iter().enumerate().skip(1).filter(|&(_, &is_start)| !is_start) {
+          if starts.len() + 1 == salesmen {
+            break;
+          }
+          starts.push(cut);
+        }
+        starts.sort_unstable();
+
+        let value = (0..salesmen)
+          .map(|route| route_length(if route == 0 { 0 } else { starts[route - 1] }, starts.get(route).map_or(count, |&next| next) - 1))
+          .fold(0.0, f64::max);
+        (value, starts)
+      }
+    };
+
+    starts.push(0);
+    starts.sort_unstable();
+    (value, customers, starts)
+  }
+}
+
+// define the routes of a solution, to display them separately
+pub struct Routes<'a> {
+  pub dataset: &'a Dataset,
+  pub routes: Vec<Vec<usize>>,
+  pub lengths: Vec<f64>
+}
+
+impl<'a> Routes<'a> {
+  pub fn new(dataset: &'a Dataset, fleet: &Fleet, nodes: &[usize]) -> Self {
+    let (_, customers, starts) = fleet.split(&dataset.distance_matrix, nodes);
+    let routes: Vec<Vec<usize>> = starts.iter()
+      .enumerate()
+      .map(|(index, &start)| customers[start..starts.get(index + 1).copied().unwrap_or(customers.len())].to_vec())
+      .collect();
+    let lengths = routes.iter()
+      .map(|route| {
+        let mut stops = vec![fleet.depot];
+        stops.extend_from_slice(route);
+        stops.push(fleet.depot);
+        stops.windows(2).map(|edge| dataset.distance_matrix.get(edge[0], edge[1])).sum()
+      })
+      .collect();
+    Self { dataset, routes, lengths }
+  }
+}
+
+// implement the Display trait for the Routes struct
+impl<'a> Display for Routes<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let fleet = self.dataset.fleet.as_ref().expect("Unable to find the fleet of the dataset");
+    let depot = &self.dataset.labels()[fleet.depot];
+
+    for (index, (route, length)) in self.routes.iter().zip(self.lengths.iter()).enumerate() {
+      let stops = route.iter().map(|&node| self.dataset.labels()[node].as_str()).collect::<Vec<&str>>().join(" -> ");
+      write!(f, "route {} · {} -> {} -> {} · {}", index + 1, depot, stops, depot, length.thousands())?;
+      if let (Some(load), Vehicles::Capacitated { capacity, .. }) = (fleet.load(route), &fleet.vehicles) {
+        write!(f, " · load {} / {}", load.thousands(), capacity.thousands())?;
+      }
+      writeln!(f)?;
+    }
+
+    let total: f64 = self.lengths.iter().sum();
+    let longest = self.lengths.iter().copied().fold(0.0, f64::max);
+    writeln!(f, "total length : {} · longest route : {}", total.thousands(), longest.thousands())
+  }
+}
@@ -0,0 +1,158 @@
+use std::fmt::Display;
+use clap::ValueEnum;
+use crate::dataset::{Dataset,Labels,Matrix};
+use crate::utils::ThousandsDisplayPolicy;
+
+// define how the tours arriving after the end of a time window are handled
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TimeWindowsMode {
+  // the lateness is added to the length of the tour, multiplied by the lateness penalty
+  Penalty,
+  // late tours are always worse than the tours on time, and can't be the final solution
+  Hard
+}
+
+// default cost of each time unit of lateness
+pub const DEFAULT_LATENESS_PENALTY: f64 = 10.0;
+
+// define the time windows and service times of the nodes of a dataset
+// travelling from a node to another takes as long as their distance, the tour starts at time 0
+// arriving before the opening of a window means waiting, arriving after its closing means being late
+pub struct TimeWindows {
+  windows: Vec<Option<(f64, f64)>>,
+  service_times: Vec<f64>,
+  pub mode: TimeWindowsMode,
+  pub lateness_penalty: f64
+}
+
+impl TimeWindows {
+  // build the time windows of a dataset, returns None if there are neither time windows nor service times
+  // empty lists mean no time windows or no service times
+  pub fn new(labels: &Labels, windows: Vec<Option<(f64, f64)>>, service_times: Vec<f64>, mode: TimeWindowsMode, lateness_penalty: f64) -> Option<Self> {
+    if windows.is_empty() && service_times.is_empty() {
+      return None;
+    }
+    let windows = if windows.is_empty() { vec![None; labels.len()] } else { windows };
+    let service_times = if service_times.is_empty() { vec![0.0; labels.len()] } else { service_times };
+
+    // there should be one time window and one service time per node
+    if windows.len() != labels.len() {
+      panic!("There should be one time window per node (or null), found {} time windows for {} nodes", windows.len(), labels.len());
+    }
+    if service_times.len() != labels.len() {
+      panic!("There should be one service time per node, found {} service times for {} nodes", service_times.len(), labels.len());
+    }
+
+    for (node, window) in windows.iter().enumerate() {
+      if let Some((open, close)) = window {
+        if open.is_nan() || close.is_nan() || open > close {
+          panic!("The time window of {} should open before it closes, found [{}, {}]", labels[node], open, close);
+        }
+      }
+    }
+    for (node, service_time) in service_times.iter().enumerate() {
+      if !service_time.is_finite() || *service_time < 0.0 {
+        panic!("The service time of {} should be a positive number, found {}", labels[node], service_time);
+      }
+    }
+    if !lateness_penalty.is_finite() || lateness_penalty < 0.0 {
+      panic!("The lateness penalty should be a positive number, found {}", lateness_penalty);
+    }
+
+    Some(Self { windows, service_times, mode, lateness_penalty })
+  }
+
+  // simulate the schedule of a tour, calling the visitor with each stop and its (arrival, start of service, lateness)
+  fn simulate<F: FnMut(usize, f64, f64, f64)>(&self, distance_matrix: &Matrix, nodes: &[usize], mut visitor: F) {
+    let mut time = 0.0;
+    for (index, &node) in nodes.iter().enumerate() {
+      if index > 0 {
+        time += distance_matrix.get(nodes[index - 1], node);
+      }
+      let arrival = time;
+      let (start, lateness) = match self.windows[node] {
+        Some((open, close)) => (arrival.max(open), (arrival - close).max(0.0)),
+        None => (arrival, 0.0)
+      };
+      visitor(node, arrival, start, lateness);
+      time = start + self.service_times[node];
+    }
+  }
+
+  // returns the total lateness of a tour and its number of late stops
+  pub fn lateness(&self, distance_matrix: &Matrix, nodes: &[usize]) -> (f64, usize) {
+    let (mut total_lateness, mut late_stops) = (0.0, 0);
+    self.simulate(distance_matrix, nodes, |_, _, _, lateness| {
+      if lateness > 0.0 {
+        total_lateness += lateness;
+        late_stops += 1;
+      }
+    });
+    (total_lateness, late_stops)
+  }
+
+  // returns the value optimized by the genetic algorithm for a tour of the given length
+  // with hard time windows, late tours are longer than the longest possible tour on time
+  pub fn objective(&self, length: f64, total_lateness: f64, longest_path_length: f64) -> f64 {
+    if total_lateness == 0.0 {
+      return length;
+    }
+    match self.mode {
+      TimeWindowsMode::Penalty => length + self.lateness_penalty * total_lateness,
+      TimeWindowsMode::Hard => longest_path_length + length + total_lateness
+    }
+  }
+}
+
+// define the schedule of a tour, to display the arrival time at each stop
+pub struct Schedule<'a> {
+  pub dataset: &'a Dataset,
+  // (node, arrival, start of service, lateness) of each stop
+  pub stops: Vec<(usize, f64, f64, f64)>,
+  pub total_lateness: f64,
+  pub late_stops: usize
+}
+
+impl<'a> Schedule<'a> {
+  pub fn new(dataset: &'a Dataset, time_windows: &TimeWindows, nodes: &[usize]) -> Self {
+    let mut stops = Vec::with_capacity(nodes.len());
+    time_windows.simulate(&dataset.distance_matrix, nodes, |node, arrival, start, lateness| stops.push((node, arrival, start, lateness)));
+    let (total_lateness, late_stops) = time_windows.lateness(&dataset.distance_matrix, nodes);
+    Self { dataset, stops, total_lateness, late_stops }
+  }
+}
+
+// implement the Display trait for the Schedule struct
+impl<'a> Display for Schedule<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let time_windows = self.dataset.time_windows.as_ref().expect("Unable to find the time windows of the dataset");
+    let format_time = |time: f64| format!("{:.2}", time).thousands();
+
+    for &(node, arrival, start, lateness) in self.stops.iter() {
+      let window = match time_windows.windows[node] {
+        Some((open, close)) => format!("[{}, {}]", format_time(open), format_time(close)),
+        None => String::from("-")
+      };
+      write!(
+        f,
+        "{:>width$} · arrival {:>12} · window {:>26} · service {:>12} -> {:>12}",
+        self.dataset.labels()[node],
+        format_time(arrival),
+        window,
+        format_time(start),
+        format_time(start + time_windows.service_times[node]),
+        width = self.dataset.longest_label_display_width
+      )?;
+      if lateness > 0.0 {
+        write!(f, " · late by {}", format_time(lateness))?;
+      }
+      writeln!(f)?;
+    }
+
+    if self.late_stops == 0 {
+      writeln!(f, "every time window is respected")
+    } else {
+      writeln!(f, "late stops : {} · total lateness : {}", self.late_stops.thousands(), format_time(self.total_lateness))
+    }
+  }
+}
@@ -0,0 +1,137 @@
+// define a 2D k-d tree over the locations of a dataset
+// the tree is stored implicitly: each subtree is a range of `order`, its root being the middle of the range
+// nodes can be removed from the tree, which lets heuristics query only the unvisited nodes
+#[derive(Clone)]
+pub struct KdTree {
+  locations: Vec<(f64, f64)>,
+  order: Vec<usize>,
+  positions: Vec<usize>,
+  alive_counts: Vec<usize>,
+  removed: Vec<bool>
+}
+
+// returns the coordinate of a location along an axis (0 for x, 1 for y)
+fn coordinate(location: (f64, f64), axis: usize) -> f64 {
+  if axis == 0 { location.0 } else { location.1 }
+}
+
+// returns the squared distance between two locations
+fn squared_distance(l1: (f64, f64), l2: (f64, f64)) -> f64 {
+  let delta_x = l2.0 - l1.0;
+  let delta_y = l2.1 - l1.1;
+  delta_x*delta_x + delta_y*delta_y
+}
+
+impl KdTree {
+  // build the tree, in O(n log n)
+  pub fn new(locations: &[(f64, f64)]) -> Self {
+    let mut tree = Self {
+      locations: locations.to_vec(),
+      order: (0..locations.len()).collect(),
+      positions: vec![0; locations.len()],
+      alive_counts: vec![0; locations.len()],
+      removed: vec![false; locations.len()]
+    };
+    tree.build(0, locations.len(), 0);
+    for (position, &node) in tree.order.iter().enumerate() {
+      tree.positions[node] = position;
+    }
+    tree
+  }
+
+  // split the range around its median along the axis of the current depth
+  fn build(&mut self, start: usize, end: usize, depth: usize) {
+    if start >= end {
+      return;
+    }
+
+    let middle = (start + end) / 2;
+    let axis = depth % 2;
+    let locations = &self.locations;
+    self.order[start..end].select_nth_unstable_by(middle - start, |&n1, &n2| {
+      coordinate(locations[n1], axis).partial_cmp(&coordinate(locations[n2], axis)).expect("Error while building the k-d tree")
+    });
+    self.alive_counts[middle] = end - start;
+
+    self.build(start, middle, depth + 1);
+    self.build(middle + 1, end, depth + 1);
+  }
+
+  // returns the location of a node
+  pub fn location(&self, node: usize) -> (f64, f64) {
+    self.locations[node]
+  }
+
+  // remove a node from the tree, in O(log n)
+  pub fn remove(&mut self, node: usize) {
+    if self.removed[node] {
+      return;
+    }
+    self.removed[node] = true;
+
+    // update the number of alive nodes of each subtree containing the node
+    let position = self.positions[node];
+    let (mut start, mut end) = (0, self.order.len());
+    loop {
+      let middle = (start + end) / 2;
+      self.alive_counts[middle] -= 1;
+      if position == middle {
+        break;
+      } else if position < middle {
+        end = middle;
+      } else {
+        start = middle + 1;
+      }
+    }
+  }
+
+  // returns the k nearest nodes of a location accepted by the filter, sorted by distance
+  pub fn k_nearest<F: Fn(usize) -> bool>(&self, location: (f64, f64), k: usize, filter: F) -> Vec<usize> {
+    let mut nearest: Vec<(f64, usize)> = Vec::with_capacity(k + 1);
+    if k > 0 {
+      self.search(0, self.order.len(), 0, location, k, &filter, &mut nearest);
+    }
+    nearest.into_iter().map(|(_, node)| node).collect()
+  }
+
+  // returns the nearest node of a location accepted by the filter
+  pub fn nearest<F: Fn(usize) -> bool>(&self, location: (f64, f64), filter: F) -> Option<usize> {
+    self.k_nearest(location, 1, filter).first().copied()
+  }
+
+  // recursively search the nearest nodes of a subtree
+  #[allow(clippy::too_many_arguments)]
+  fn search<F: Fn(usize) -> bool>(&self, start: usize, end: usize, depth: usize, location: (f64, f64), k: usize, filter: &F, nearest: &mut Vec<(f64, usize)>) {
+    if start >= end {
+      return;
+    }
+    let middle = (start + end) / 2;
+    if self.alive_counts[middle] == 0 {
+      return;
+    }
+
+    // try the root of the subtree, keeping the nearest nodes sorted
+    let node = self.order[middle];
+    if !self.removed[node] && filter(node) {
+      let distance = squared_distance(location, self.locations[node]);
+      if nearest.len() < k || distance < nearest[nearest.len() - 1].0 {
+        let index = nearest.partition_point(|&(d, _)| d <= distance);
+        nearest.insert(index, (distance, node));
+        nearest.truncate(k);
+      }
+    }
+
+    // search the side of the location first, then the other side if it can contain nearer nodes
+    let axis = depth % 2;
+    let delta = coordinate(location, axis) - coordinate(self.locations[node], axis);
+    let (near, far) = if delta < 0.0 {
+      ((start, middle), (middle + 1, end))
+    } else {
+      ((middle + 1, end), (start, middle))
+    };
+    self.search(near.0, near.1, depth + 1, location, k, filter, nearest);
+    if nearest.len() < k || delta*delta < nearest[nearest.len() - 1].0 {
+      self.search(far.0, far.1, depth + 1, location, k, filter, nearest);
+    }
+  }
+}
@@ -0,0 +1,209 @@
+use clap::ValueEnum;
+
+// define the precision used to store the distances of a matrix
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Precision {
+  // 64 bits floats, exact values
+  F64,
+  // 32 bits floats, half the memory of f64
+  F32,
+  // 32 bits integers, distances should be integers or rounded
+  Integer
+}
+
+// define the layout used to store the distances of a matrix
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Layout {
+  // triangular if the matrix is symmetric, full otherwise
+  Auto,
+  // every distance is stored
+  Full,
+  // only the upper triangle is stored, the matrix must be symmetric
+  Triangular
+}
+
+// define a distance matrix stored in a single contiguous vector
+// symmetric matrices can store only their upper triangle (diagonal included), using half the memory
+#[derive(Clone)]
+pub struct FlatMatrix<T> {
+  size: usize,
+  triangular: bool,
+  values: Vec<T>
+}
+
+impl<T: Copy + PartialEq + Into<f64>> FlatMatrix<T> {
+  // create a matrix by computing each of its values
+  // with a triangular layout, only the values of the upper triangle are computed
+  pub fn from_fn<F: Fn(usize, usize) -> T>(size: usize, triangular: bool, value: F) -> Self {
+    let mut values = Vec::with_capacity(if triangular { size * (size + 1) / 2 } else { size * size });
+    for i in 0..size {
+      let start = if triangular { i } else { 0 };
+      for j in start..size {
+        values.push(value(i, j));
+      }
+    }
+    Self { size, triangular, values }
+  }
+
+  // returns the position of a value in the vector
+  #[inline]
+  fn index(&self, node1: usize, node2: usize) -> usize {
+    if self.triangular {
+      let (row, column) = if node1 <= node2 { (node1, node2) } else { (node2, node1) };
+      // rows above take size + (size - 1) + ... + (size - row + 1) values
+      row * self.size - row * (row.saturating_sub(1)) / 2 + column - row
+    } else {
+      node1 * self.size + node2
+    }
+  }
+
+  #[inline]
+  pub fn get(&self, node1: usize, node2: usize) -> f64 {
+    self.values[self.index(node1, node2)].into()
+  }
+
+  pub fn len(&self) -> usize {
+    self.size
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.size == 0
+  }
+
+  pub fn is_symmetric(&self) -> bool {
+    self.triangular || (0..self.size).all(|i| (0..i).all(|j| self.values[self.index(i, j)] == self.values[self.index(j, i)]))
+  }
+
+  // returns the longest distance, forbidden edges excluded
+  pub fn max(&self, forbidden: T) -> f64 {
+    self.values.iter().filter(|&&value| value != forbidden).map(|&value| value.into()).fold(f64::NEG_INFINITY, f64::max)
+  }
+
+  // returns the memory used by the values, in bytes
+  pub fn memory_size(&self) -> usize {
+    self.values.len() * std::mem::size_of::<T>()
+  }
+}
+
+// define how the distances between locations are cached
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CacheMode {
+  // full cache for small datasets, neighbors cache for big ones
+  Auto,
+  // distances are computed each time they are needed
+  None,
+  // the whole distance matrix is computed when loading the dataset
+  Full,
+  // only the distances to the candidate neighbors are computed when loading the dataset
+  Neighbors
+}
+
+// above this number of nodes, the automatic cache mode only caches the distances to the candidate neighbors:
+// the full matrix doesn't fit in the CPU caches anymore and reading it becomes slower than computing the distances
+// (compare both on a dataset with the storage benchmark)
+pub const FULL_CACHE_MAX_SIZE: usize = 2_000;
+
+// compute the euclidean distance between two locations
+#[inline]
+pub fn euclidean_distance(location1: (f64, f64), location2: (f64, f64)) -> f64 {
+  let delta_x = location2.0 - location1.0;
+  let delta_y = location2.1 - location1.1;
+
+  (delta_x*delta_x + delta_y*delta_y).sqrt()
+}
+
+// define how distances are rounded to integers, following the TSPLIB rules
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Rounding {
+  // distances are kept as they are
+  None,
+  // distances are rounded to the nearest integer (EUC_2D)
+  Nint,
+  // distances are rounded up (CEIL_2D)
+  Ceil,
+  // pseudo-euclidean distance of the att48 and att532 instances (ATT), only for locations
+  Att
+}
+
+impl Rounding {
+  // round a distance read from a matrix
+  // datasets made of a distance matrix are rejected with the ATT rounding when they are loaded
+  pub fn round(&self, distance: f64) -> f64 {
+    match self {
+      Self::None => distance,
+      Self::Nint => (distance + 0.5).floor(),
+      Self::Ceil => distance.ceil(),
+      Self::Att => panic!("The ATT rounding can only be used with datasets made of locations")
+    }
+  }
+
+  // compute the distance between two locations
+  #[inline]
+  pub fn distance(&self, location1: (f64, f64), location2: (f64, f64)) -> f64 {
+    match self {
+      Self::Att => {
+        let delta_x = location2.0 - location1.0;
+        let delta_y = location2.1 - location1.1;
+        let distance = ((delta_x*delta_x + delta_y*delta_y) / 10.0).sqrt();
+        let rounded = (distance + 0.5).floor();
+        if rounded < distance { rounded + 1.0 } else { rounded }
+      },
+      _ => self.round(euclidean_distance(location1, location2))
+    }
+  }
+}
+
+// define a cache of the distances between each location and its candidate neighbors
+// other distances are computed from the locations
+pub struct NeighborsCache {
+  locations: Vec<(f64, f64)>,
+  rounding: Rounding,
+  offsets: Vec<usize>,
+  neighbors: Vec<u32>,
+  distances: Vec<f64>
+}
+
+impl NeighborsCache {
+  pub fn new(locations: Vec<(f64, f64)>, rounding: Rounding, nodes_neighbors: &[Vec<usize>]) -> Self {
+    let mut offsets = Vec::with_capacity(locations.len() + 1);
+    let mut neighbors = Vec::new();
+    let mut distances = Vec::new();
+
+    // store the neighbors of every node one after the other
+    offsets.push(0);
+    for (node, node_neighbors) in nodes_neighbors.iter().enumerate() {
+      for &neighbor in node_neighbors.iter() {
+        neighbors.push(neighbor as u32);
+        distances.push(rounding.distance(locations[node], locations[neighbor]));
+      }
+      offsets.push(neighbors.len());
+    }
+
+    Self { locations, rounding, offsets, neighbors, distances }
+  }
+
+  #[inline]
+  pub fn get(&self, node1: usize, node2: usize) -> f64 {
+    let (start, end) = (self.offsets[node1], self.offsets[node1 + 1]);
+    match self.neighbors[start..end].iter().position(|&neighbor| neighbor as usize == node2) {
+      Some(index) => self.distances[start + index],
+      None => self.rounding.distance(self.locations[node1], self.locations[node2])
+    }
+  }
+
+  pub fn locations(&self) -> &[(f64, f64)] {
+    &self.locations
+  }
+
+  pub fn rounding(&self) -> Rounding {
+    self.rounding
+  }
+
+  // returns the memory used by the locations and the cache, in bytes
+  pub fn memory_size(&self) -> usize {
+    self.locations.len() * std::mem::size_of::<(f64, f64)>()
+      + self.offsets.len() * std::mem::size_of::<usize>()
+      + self.neighbors.len() * std::mem::size_of::<u32>()
+      + self.distances.len() * std::mem::size_of::<f64>()
+  }
+}
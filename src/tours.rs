@@ -0,0 +1,186 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::fs::read_to_string;
+use serde::Deserialize;
+use crate::cvrplib;
+use crate::dataset::{Dataset,Labels};
+use crate::individual::Individual;
+use crate::schedule::TimeWindowsMode;
+use crate::utils::ThousandsDisplayPolicy;
+
+// define the content of a tour file in the JSON format : a list of labels, or an object with a list of labels
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonTour {
+  Labels(Labels),
+  Object { tour: Labels }
+}
+
+// returns the labels of the nodes of a tour file, in the order they are visited
+// TSPLIB tours are recognized by their .tour extension and JSON tours by their .json extension,
+// the other files being lists of labels, one per line
+pub fn read_tour_file(file_name: &str) -> Labels {
+  // verify that the given file exists
+  if !Path::new(file_name).exists() {
+    panic!("The given tour file does not exist");
+  }
+  let content = read_to_string(file_name).expect("Unable to read the tour file");
+
+  match Path::new(file_name).extension().and_then(|extension| extension.to_str()) {
+    Some("tour") => cvrplib::parse_tour(&content),
+    Some("json") => match serde_json::from_str(&content).expect("Unable to parse the tour file") {
+      JsonTour::Labels(labels) | JsonTour::Object { tour: labels } => labels
+    },
+    _ => content.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from).collect()
+  }
+}
+
+// whether the tours of a dataset can skip some of its nodes
+fn can_skip_nodes(dataset: &Dataset) -> bool {
+  dataset.orienteering.is_some() || dataset.clusters.is_some() || dataset.node_costs.as_ref().is_some_and(|node_costs| node_costs.has_optional_nodes())
+}
+
+// returns the nodes of a tour given by its labels, followed by the nodes it leaves out, and the number of nodes it visits
+// the tour should visit every node, unless the dataset allows skipping some of them, and satisfy the constraints of the dataset
+pub fn tour_nodes(dataset: &Dataset, labels: &[String]) -> Result<(Vec<usize>, usize), String> {
+  let mut nodes = Vec::with_capacity(dataset.size);
+  let mut visited = vec![false; dataset.size];
+  for label in labels.iter() {
+    let node = dataset.node(label).map_err(|_| format!("the tour visits the unknown node {}", label))?;
+    if visited[node] {
+      return Err(format!("{} is visited twice", label));
+    }
+    visited[node] = true;
+    nodes.push(node);
+  }
+
+  // the skipped nodes follow the visited ones
+  let visited_count = nodes.len();
+  if visited_count == 0 {
+    return Err(String::from("the tour doesn't visit any node"));
+  }
+  if visited_count < dataset.size && !can_skip_nodes(dataset) {
+    return Err(format!("the tour visits {} nodes instead of {}", visited_count, dataset.size));
+  }
+  nodes.extend((0..dataset.size).filter(|&node| !visited[node]));
+
+  dataset.constraints.check(dataset.labels(), &dataset.distance_matrix, &nodes)?;
+  Ok((nodes, visited_count))
+}
+
+// define the evaluation of a tour given by its labels : the cost of each of its edges, its length and the rules of the dataset it breaks
+pub struct TourEvaluation<'a> {
+  pub dataset: &'a Dataset,
+  // None if the tour doesn't visit any node of the dataset
+  pub individual: Option<Individual<'a>>,
+  pub edges: Vec<(usize, usize, f64)>,
+  // total lateness and number of late stops, only computed for datasets with time windows
+  pub lateness: Option<(f64, usize)>,
+  pub violations: Vec<String>
+}
+
+impl<'a> TourEvaluation<'a> {
+  // evaluate a tour as it is, without repairing it
+  pub fn new(dataset: &'a Dataset, labels: &[String]) -> Self {
+    let mut violations = Vec::new();
+    let mut nodes = Vec::with_capacity(dataset.size);
+    let mut visited = vec![false; dataset.size];
+    for label in labels.iter() {
+      match dataset.node(label) {
+        Err(_) => violations.push(format!("the tour visits the unknown node {}", label)),
+        Ok(node) if visited[node] => violations.push(format!("{} is visited twice", label)),
+        Ok(node) => {
+          visited[node] = true;
+          nodes.push(node);
+        }
+      }
+    }
+
+    // the skipped nodes follow the visited ones
+    let visited_count = nodes.len();
+    let skipped_nodes: Vec<usize> = (0..dataset.size).filter(|&node| !visited[node]).collect();
+    if !skipped_nodes.is_empty() && !can_skip_nodes(dataset) {
+      let skipped_labels = skipped_nodes.iter().map(|&node| dataset.labels()[node].as_str()).collect::<Vec<&str>>().join(", ");
+      violations.push(format!("the tour doesn't visit {}", skipped_labels));
+    }
+    nodes.extend(skipped_nodes);
+    if visited_count == 0 {
+      violations.push(String::from("the tour doesn't visit any node"));
+      return Self { dataset, individual: None, edges: Vec::new(), lateness: None, violations };
+    }
+
+    // the constraints can only be verified on tours visiting every node once
+    if violations.is_empty() {
+      violations.extend(dataset.constraints.violations(dataset.labels(), &dataset.distance_matrix, &nodes));
+    }
+    let visited_nodes = &nodes[..visited_count];
+    if let Some(orienteering) = &dataset.orienteering {
+      if visited_nodes[0] != orienteering.depot {
+        violations.push(format!("the tour should start from the depot {}", dataset.labels()[orienteering.depot]));
+      }
+      let length = orienteering.length(&dataset.distance_matrix, visited_nodes);
+      if length > orienteering.budget {
+        violations.push(format!("the tour is longer than the budget ({} / {})", length.thousands(), orienteering.budget.thousands()));
+      }
+    }
+    if let Some(clusters) = &dataset.clusters {
+      violations.extend(clusters.violations(dataset.labels(), visited_nodes));
+    }
+    if let Some(node_costs) = &dataset.node_costs {
+      for node in node_costs.skipped_mandatory_nodes(&nodes, visited_count) {
+        violations.push(format!("{} can't be skipped", dataset.labels()[node]));
+      }
+    }
+    // late stops are only forbidden by hard time windows, but always reported
+    let lateness = dataset.time_windows.as_ref().map(|time_windows| time_windows.lateness(&dataset.distance_matrix, &nodes));
+    if let (Some(time_windows), Some((_, late_stops))) = (&dataset.time_windows, lateness) {
+      if time_windows.mode == TimeWindowsMode::Hard && late_stops > 0 {
+        violations.push(format!("{} stops are reached after the end of their time window", late_stops.thousands()));
+      }
+    }
+
+    // the tour of an orienteering problem goes back to the depot
+    let mut edges: Vec<(usize, usize, f64)> = visited_nodes.windows(2).map(|edge| (edge[0], edge[1], dataset.distance_matrix.get(edge[0], edge[1]))).collect();
+    if let Some(orienteering) = dataset.orienteering.as_ref().filter(|orienteering| visited_nodes[visited_count - 1] != orienteering.depot) {
+      let last = visited_nodes[visited_count - 1];
+      edges.push((last, orienteering.depot, dataset.distance_matrix.get(last, orienteering.depot)));
+    }
+
+    let individual = Some(Individual::from_tour(dataset, nodes, visited_count));
+    Self { dataset, individual, edges, lateness, violations }
+  }
+
+  // whether the tour breaks none of the rules of the dataset
+  pub fn is_valid(&self) -> bool {
+    self.violations.is_empty()
+  }
+}
+
+// implement the Display trait for the TourEvaluation struct
+impl<'a> Display for TourEvaluation<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for &(from, to, distance) in self.edges.iter() {
+      writeln!(f, "{} -> {} · {}", self.dataset.labels()[from], self.dataset.labels()[to], distance.thousands())?;
+    }
+    if let Some(individual) = &self.individual {
+      writeln!(f, "length : {}", individual.tour_length().thousands())?;
+      if self.dataset.has_objective() {
+        writeln!(f, "objective : {}", individual.display_objective())?;
+      }
+      for ((name, _), cost) in self.dataset.cost_matrices.iter().zip(individual.costs.iter()) {
+        writeln!(f, "{} : {}", name, cost.thousands())?;
+      }
+    }
+    if let Some((total_lateness, late_stops)) = self.lateness {
+      writeln!(f, "late stops : {} · total lateness : {}", late_stops.thousands(), format!("{:.2}", total_lateness).thousands())?;
+    }
+    if self.violations.is_empty() {
+      return writeln!(f, "the tour is valid");
+    }
+    writeln!(f, "violations : {}", self.violations.len().thousands())?;
+    for violation in self.violations.iter() {
+      writeln!(f, "- {}", violation)?;
+    }
+    Ok(())
+  }
+}
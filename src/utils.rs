@@ -2,7 +2,7 @@ use thousands::{SeparatorPolicy,digits,Separable};
 use crate::dataset::Matrix;
 
 // function that returns the maximum display width of a vector
-pub fn get_max_display_width<T: ToString>(arr: &Vec<T>) -> usize {
+pub fn get_max_display_width<T: ToString>(arr: &[T]) -> usize {
   arr.iter().map(|x| x.to_string().len()).max().expect("Unable to find the maximum display width")
 }
 pub fn get_max_display_width_thousands_2d(matrix: &Matrix) -> usize {
@@ -0,0 +1,87 @@
+use rand::prelude::*;
+use tsp_solver::bounds::{assignment_bound,held_karp_bound,one_tree_bound,HELD_KARP_ITERATIONS};
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix};
+use tsp_solver::exact::{self,TourKind,DEFAULT_NODE_LIMIT};
+use tsp_solver::storage::{Layout,Precision,Rounding};
+
+// tolerance on the bounds, which sum floating point distances in another order than the tours
+const EPSILON: f64 = 1e-6;
+
+fn load(file_name: &str, options: &DatasetOptions) -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file_name), options)
+}
+
+// build a dataset from a random asymmetric distance matrix
+fn random_asymmetric_dataset(size: usize, rng: &mut StdRng) -> Dataset {
+  let rows: Vec<Vec<f64>> = (0..size)
+    .map(|node1| (0..size).map(|node2| if node1 == node2 { 0.0 } else { rng.gen_range(1..100) as f64 }).collect())
+    .collect();
+  let labels: Vec<String> = (0..size).map(|node| format!("N{}", node)).collect();
+  let matrix = Matrix::from_rows(&rows, Precision::F64, Layout::Auto, Rounding::None);
+  Dataset::from_matrix(labels, matrix, &DatasetOptions::default())
+}
+
+// verify that the bounds don't exceed the optimum, and that the Held-Karp bound improves on the 1-tree one
+fn check_bounds(dataset: &Dataset, kind: TourKind, optimum: f64) {
+  let one_tree = one_tree_bound(dataset, kind);
+  let held_karp = held_karp_bound(dataset, kind, optimum, HELD_KARP_ITERATIONS);
+  assert!(one_tree <= optimum + EPSILON, "1-tree bound {} above the optimum {}", one_tree, optimum);
+  assert!(held_karp <= optimum + EPSILON, "Held-Karp bound {} above the optimum {}", held_karp, optimum);
+  assert!(held_karp >= one_tree - EPSILON, "Held-Karp bound {} below the 1-tree bound {}", held_karp, one_tree);
+}
+
+#[test]
+fn bounds_of_the_demo_datasets_are_below_the_optimum() {
+  for file_name in ["demo/demo.json", "demo/demo_forbidden.json", "demo/demo_fixed.json", "demo/demo_precedences.json"] {
+    let dataset = load(file_name, &DatasetOptions::default());
+    for kind in [TourKind::Open, TourKind::Closed] {
+      let optimum = exact::solve(&dataset, kind, DEFAULT_NODE_LIMIT).length;
+      check_bounds(&dataset, kind, optimum);
+      assert!(assignment_bound(&dataset, kind) <= optimum + EPSILON);
+    }
+  }
+}
+
+#[test]
+fn bounds_of_att48_are_below_its_optimum() {
+  let options = DatasetOptions { rounding: Rounding::Att, ..DatasetOptions::default() };
+  let dataset = load("att48/att48_locations.json", &options);
+  check_bounds(&dataset, TourKind::Closed, 10628.0);
+
+  // the Held-Karp bound is known to be within a few percent of the optimum
+  let held_karp = held_karp_bound(&dataset, TourKind::Closed, 10628.0, HELD_KARP_ITERATIONS);
+  assert!(held_karp >= 0.95 * 10628.0, "Held-Karp bound {} too far from the optimum", held_karp);
+}
+
+#[test]
+fn assignment_bound_of_asymmetric_datasets_is_below_the_optimum() {
+  let mut rng = StdRng::seed_from_u64(42);
+  for size in 3..=9 {
+    for _ in 0..5 {
+      let dataset = random_asymmetric_dataset(size, &mut rng);
+      assert!(!dataset.distance_matrix.is_symmetric());
+      for kind in [TourKind::Open, TourKind::Closed] {
+        let optimum = exact::held_karp(&dataset, kind).length;
+        let assignment = assignment_bound(&dataset, kind);
+        assert!(assignment <= optimum + EPSILON, "assignment bound {} above the optimum {}", assignment, optimum);
+        assert!(assignment > 0.0);
+        check_bounds(&dataset, kind, optimum);
+      }
+    }
+  }
+}
+
+#[test]
+fn assignment_bound_of_a_single_cycle_matrix_is_exact() {
+  // going 0 -> 1 -> 2 -> 3 -> 0 costs 1 per edge, every other edge costs 10
+  let rows: Vec<Vec<f64>> = (0..4)
+    .map(|node1| (0..4).map(|node2| if node1 == node2 { 0.0 } else if node2 == (node1 + 1) % 4 { 1.0 } else { 10.0 }).collect())
+    .collect();
+  let labels: Vec<String> = (0..4).map(|node| format!("N{}", node)).collect();
+  let matrix = Matrix::from_rows(&rows, Precision::F64, Layout::Auto, Rounding::None);
+  let dataset = Dataset::from_matrix(labels, matrix, &DatasetOptions::default());
+
+  assert_eq!(assignment_bound(&dataset, TourKind::Closed), 4.0);
+  // the open tour leaves the dummy node and goes back to it for free
+  assert_eq!(assignment_bound(&dataset, TourKind::Open), 3.0);
+}
@@ -0,0 +1,120 @@
+use rand::prelude::*;
+use tsp_solver::candidates::{alpha_nearness,delaunay_neighbors,quadrant_neighbors};
+use tsp_solver::dataset::Matrix;
+use tsp_solver::spatial::KdTree;
+use tsp_solver::storage::Rounding;
+
+// returns random locations in a square
+fn random_locations(size: usize, rng: &mut StdRng) -> Vec<(f64, f64)> {
+  (0..size).map(|_| (rng.gen_range(0.0..1000.0), rng.gen_range(0.0..1000.0))).collect()
+}
+
+// returns the locations of a square grid, with a distance of 1 between neighbors
+fn grid_locations(side: usize) -> Vec<(f64, f64)> {
+  (0..side * side).map(|node| ((node % side) as f64, (node / side) as f64)).collect()
+}
+
+// squared distance between two locations, enough to compare distances
+fn squared_distance(l1: (f64, f64), l2: (f64, f64)) -> f64 {
+  (l2.0 - l1.0).powi(2) + (l2.1 - l1.1).powi(2)
+}
+
+#[test]
+fn quadrant_candidates_cover_every_non_empty_quadrant() {
+  let mut rng = StdRng::seed_from_u64(42);
+  let quadrant = |origin: (f64, f64), location: (f64, f64)| match (location.0 >= origin.0, location.1 >= origin.1) {
+    (true, true) => 0,
+    (false, true) => 1,
+    (false, false) => 2,
+    (true, false) => 3
+  };
+
+  for locations in [random_locations(300, &mut rng), grid_locations(12)] {
+    let neighbors = quadrant_neighbors(&locations, &KdTree::new(&locations), 8);
+    for (node, node_neighbors) in neighbors.iter().enumerate() {
+      assert_eq!(node_neighbors.len(), 8);
+      assert!(!node_neighbors.contains(&node));
+      for q in 0..4 {
+        let others = (0..locations.len()).filter(|&other| other != node && quadrant(locations[node], locations[other]) == q).count();
+        let candidates = node_neighbors.iter().filter(|&&neighbor| quadrant(locations[node], locations[neighbor]) == q).count();
+        assert!(candidates >= others.min(2), "node {} has {} candidates in quadrant {} out of {} nodes", node, candidates, q, others);
+      }
+    }
+  }
+}
+
+#[test]
+fn delaunay_neighbors_of_a_grid_are_its_grid_neighbors() {
+  let side = 10;
+  let locations = grid_locations(side);
+  let neighbors = delaunay_neighbors(&locations, &KdTree::new(&locations), 8);
+
+  for (node, node_neighbors) in neighbors.iter().enumerate() {
+    // the orthogonal grid neighbors are the closest ones, then come at most the diagonal ones
+    let (x, y) = (node % side, node / side);
+    let mut grid_neighbors = Vec::new();
+    if x > 0 { grid_neighbors.push(node - 1); }
+    if x + 1 < side { grid_neighbors.push(node + 1); }
+    if y > 0 { grid_neighbors.push(node - side); }
+    if y + 1 < side { grid_neighbors.push(node + side); }
+
+    let mut closest = node_neighbors[..grid_neighbors.len()].to_vec();
+    closest.sort_unstable();
+    grid_neighbors.sort_unstable();
+    assert_eq!(closest, grid_neighbors);
+    for &neighbor in node_neighbors[grid_neighbors.len()..].iter() {
+      assert_eq!(squared_distance(locations[node], locations[neighbor]), 2.0);
+    }
+  }
+}
+
+#[test]
+fn delaunay_neighbors_of_random_locations_form_a_triangulation() {
+  let mut rng = StdRng::seed_from_u64(7);
+  let locations = random_locations(2000, &mut rng);
+  let spatial_index = KdTree::new(&locations);
+  let neighbors = delaunay_neighbors(&locations, &spatial_index, usize::MAX);
+
+  // a planar triangulation has less than 3n edges, so a mean degree below 6
+  let degrees: usize = neighbors.iter().map(|node_neighbors| node_neighbors.len()).sum();
+  assert!(degrees < 6 * locations.len());
+
+  for (node, node_neighbors) in neighbors.iter().enumerate() {
+    assert!(node_neighbors.len() >= 2);
+    // the neighbors are symmetric, and the nearest node is always a Delaunay neighbor
+    for &neighbor in node_neighbors.iter() {
+      assert!(neighbors[neighbor].contains(&node));
+    }
+    assert_eq!(node_neighbors[0], spatial_index.nearest(locations[node], |other| other != node).expect("Unable to find the nearest node"));
+  }
+}
+
+#[test]
+fn delaunay_neighbors_of_collinear_locations_are_never_empty() {
+  let locations: Vec<(f64, f64)> = (0..50).map(|node| (node as f64, 2.0 * node as f64)).collect();
+  let neighbors = delaunay_neighbors(&locations, &KdTree::new(&locations), 4);
+  for (node, node_neighbors) in neighbors.iter().enumerate() {
+    assert!(!node_neighbors.is_empty());
+    assert!(!node_neighbors.contains(&node));
+    // the closest node along the line is always a candidate
+    let previous_or_next = if node == 0 { 1 } else { node - 1 };
+    assert!(node_neighbors.contains(&previous_or_next) || node_neighbors.contains(&(node + 1)));
+  }
+}
+
+#[test]
+fn alpha_candidates_include_the_nearest_node() {
+  let mut rng = StdRng::seed_from_u64(3);
+  let locations = random_locations(200, &mut rng);
+  let spatial_index = KdTree::new(&locations);
+  let neighbors = alpha_nearness(&Matrix::Locations(locations.clone(), Rounding::None), 5);
+
+  for (node, node_neighbors) in neighbors.iter().enumerate() {
+    assert_eq!(node_neighbors.len(), 5);
+    assert!(!node_neighbors.contains(&node));
+    // the edge to the nearest node is in every minimum spanning tree, so it has an alpha of 0 without penalties
+    // and stays among the best candidates with them
+    let nearest = spatial_index.nearest(locations[node], |other| other != node).expect("Unable to find the nearest node");
+    assert!(node_neighbors.contains(&nearest), "node {} doesn't have its nearest node {} as a candidate", node, nearest);
+  }
+}
@@ -0,0 +1,66 @@
+use rand::prelude::*;
+use tsp_solver::dataset::{Dataset,DatasetOptions};
+use tsp_solver::generation::Generation;
+use tsp_solver::individual::Individual;
+
+fn load(file_name: &str) -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file_name), &DatasetOptions::default())
+}
+
+// verify that the individual is a permutation of the nodes visiting exactly one node per cluster
+fn check_individual(dataset: &Dataset, individual: &Individual) {
+  let clusters = dataset.clusters.as_ref().expect("The dataset should have clusters");
+  let mut sorted_nodes = individual.nodes.clone();
+  sorted_nodes.sort_unstable();
+  assert_eq!(sorted_nodes, (0..dataset.size).collect::<Vec<usize>>());
+
+  assert_eq!(individual.visited, clusters.count());
+  assert_eq!(clusters.violations(dataset.labels(), &individual.nodes[..individual.visited]), Vec::<String>::new());
+}
+
+#[test]
+fn normalized_tours_visit_one_node_per_cluster() {
+  let dataset = load("demo/demo_clusters.json");
+  // Par is alone in its cluster, the 6 other cities are grouped by pairs
+  assert_eq!(dataset.clusters.as_ref().expect("The dataset should have clusters").count(), 4);
+
+  let mut rng = StdRng::seed_from_u64(42);
+  for _ in 0..500 {
+    let mut nodes: Vec<usize> = (0..dataset.size).collect();
+    nodes.shuffle(&mut rng);
+    let individual = Individual::from_visited_nodes(&dataset, nodes.clone(), rng.gen_range(0..=dataset.size));
+    check_individual(&dataset, &individual);
+
+    // the first node of each cluster is its representative, and both the representatives and the others keep their order
+    let position = |node: usize| nodes.iter().position(|&other| other == node).expect("Unable to find the node");
+    assert!(individual.nodes[..individual.visited].windows(2).all(|pair| position(pair[0]) < position(pair[1])));
+    assert!(individual.nodes[individual.visited..].windows(2).all(|pair| position(pair[0]) < position(pair[1])));
+  }
+}
+
+#[test]
+fn violations_list_the_clusters_not_visited_once() {
+  let dataset = load("demo/demo_clusters.json");
+  let clusters = dataset.clusters.as_ref().expect("The dataset should have clusters");
+  let node = |label: &str| dataset.labels().iter().position(|other| other == label).expect("Unable to find the node");
+
+  assert!(clusters.violations(dataset.labels(), &[node("Par"), node("Mar"), node("Lyo"), node("Tou")]).is_empty());
+  let violations = clusters.violations(dataset.labels(), &[node("Par"), node("Mar"), node("Nic"), node("Lyo")]);
+  assert_eq!(violations, vec![
+    "the cluster [Mar, Nic] is visited 2 times instead of once".to_string(),
+    "the cluster [Tou, Bez] is visited 0 times instead of once".to_string()
+  ]);
+}
+
+#[test]
+fn evolved_tours_visit_one_node_per_cluster() {
+  let dataset = load("demo/demo_clusters.json");
+  let mut rng = rand::thread_rng();
+  let mut generation = Generation::new(0, 30, 50, 5, &dataset, &mut rng);
+  for _ in 0..30 {
+    for individual in generation.population.iter() {
+      check_individual(&dataset, individual);
+    }
+    generation = generation.evolve(&mut rng, 4, 10);
+  }
+}
@@ -0,0 +1,125 @@
+use rand::prelude::*;
+use tsp_solver::constraints::Constraints;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix,Variants};
+use tsp_solver::generation::Generation;
+use tsp_solver::individual::Individual;
+use tsp_solver::storage::{Layout,Precision,Rounding};
+
+fn load(file_name: &str) -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file_name), &DatasetOptions::default())
+}
+
+// returns the forbidden edges used by an individual
+fn forbidden_edges(dataset: &Dataset, individual: &Individual) -> Vec<(usize, usize)> {
+  individual.nodes.windows(2).map(|edge| (edge[0], edge[1])).filter(|&(from, to)| !dataset.distance_matrix.get(from, to).is_finite()).collect()
+}
+
+// build a dataset of random locations with the given fixed segments and precedences, the nodes being labeled N0, N1...
+fn random_dataset(size: usize, fixed_segments: &[&[usize]], precedences: &[(usize, usize)], rng: &mut StdRng) -> Dataset {
+  let locations: Vec<(f64, f64)> = (0..size).map(|_| (rng.gen_range(0.0..1000.0), rng.gen_range(0.0..1000.0))).collect();
+  let labels: Vec<String> = (0..size).map(|node| format!("N{}", node)).collect();
+  let fixed_segments: Vec<Vec<String>> = fixed_segments.iter().map(|segment| segment.iter().map(|&node| labels[node].clone()).collect()).collect();
+  let precedences: Vec<(String, String)> = precedences.iter().map(|&(before, after)| (labels[before].clone(), labels[after].clone())).collect();
+  let constraints = Constraints::new(&labels, &[], &fixed_segments, &precedences);
+  Dataset::new(labels, Matrix::Locations(locations, Rounding::None), constraints, Variants::default(), &DatasetOptions::default())
+}
+
+// run the genetic operators on a dataset and check every individual they create : the random and repaired ones,
+// the children of crossovers, the mutants and the populations of the generations
+fn check_operators<F: Fn(&Individual)>(dataset: &Dataset, check: F) {
+  let mut rng = rand::thread_rng();
+  for _ in 0..200 {
+    let mut nodes: Vec<usize> = (0..dataset.size).collect();
+    nodes.shuffle(&mut rng);
+    check(&Individual::from_nodes(dataset, nodes));
+    check(&Individual::new(dataset, &mut rng));
+  }
+
+  let mut generation = Generation::new(0, 20, 50, 5, dataset, &mut rng);
+  for _ in 0..20 {
+    generation.population.iter().for_each(&check);
+    for _ in 0..50 {
+      let child = Individual::crossover(generation.select_parent(&mut rng), generation.select_parent(&mut rng));
+      check(&child);
+      check(&child.mutate(&mut rng, 4, 10));
+    }
+    generation = generation.evolve(&mut rng, 4, 10);
+  }
+}
+
+// evolve a dataset, returning the best individual of each generation
+fn evolve(dataset: &Dataset, generations: usize) -> Vec<Individual<'_>> {
+  let mut rng = rand::thread_rng();
+  let mut generation = Generation::new(0, generations, 50, 5, dataset, &mut rng);
+  let mut best_individuals = vec![generation.population[0].clone()];
+  for _ in 1..generations {
+    generation = generation.evolve(&mut rng, 4, 10);
+    best_individuals.push(generation.population[0].clone());
+  }
+  best_individuals
+}
+
+#[test]
+fn best_individuals_avoid_forbidden_edges() {
+  // every node can only go to the 3 nodes before and after it on a ring, the other edges being forbidden (null)
+  let size = 30;
+  let mut rng = StdRng::seed_from_u64(42);
+  let rows: Vec<Vec<f64>> = (0..size)
+    .map(|node1| (0..size).map(|node2| match (node2 + size - node1) % size {
+      0 => 0.0,
+      1..=3 => rng.gen_range(1..100) as f64,
+      offset if offset >= size - 3 => rng.gen_range(1..100) as f64,
+      _ => f64::INFINITY
+    }).collect())
+    .collect();
+  let labels: Vec<String> = (0..size).map(|node| format!("N{}", node)).collect();
+  let ring = Dataset::from_matrix(labels, Matrix::from_rows(&rows, Precision::F64, Layout::Auto, Rounding::None), &DatasetOptions::default());
+
+  for dataset in [load("demo/demo_forbidden.json"), ring] {
+    assert!(dataset.allowed_edges.is_some());
+    for individual in evolve(&dataset, 30).iter() {
+      assert!(individual.length.is_finite(), "best individual of infinite length : {:?}", individual.nodes);
+      assert_eq!(forbidden_edges(&dataset, individual), Vec::new());
+    }
+  }
+}
+
+#[test]
+fn fixed_segments_stay_contiguous_and_in_order() {
+  let mut rng = StdRng::seed_from_u64(7);
+  let random = random_dataset(30, &[&[0, 1, 2], &[10, 5, 20, 7], &[15, 16], &[29, 3]], &[], &mut rng);
+  for dataset in [load("demo/demo_fixed.json"), random] {
+    let segments = dataset.constraints.segments.clone();
+    assert!(!segments.is_empty());
+    check_operators(&dataset, |individual| {
+      let mut positions = vec![0; dataset.size];
+      for (position, &node) in individual.nodes.iter().enumerate() {
+        positions[node] = position;
+      }
+      for segment in segments.iter() {
+        assert!(segment.windows(2).all(|edge| positions[edge[1]] == positions[edge[0]] + 1), "segment {:?} broken by {:?}", segment, individual.nodes);
+      }
+    });
+  }
+}
+
+#[test]
+fn precedences_are_respected_by_every_operator() {
+  let mut rng = StdRng::seed_from_u64(3);
+  // chains and a node that must follow several ones, some of them inside a fixed segment
+  let precedences = [(0, 5), (5, 9), (9, 14), (3, 20), (11, 20), (25, 20), (27, 1), (12, 2)];
+  let random = random_dataset(30, &[&[12, 13, 14]], &precedences, &mut rng);
+  for dataset in [load("demo/demo_precedences.json"), random] {
+    let precedences = dataset.constraints.precedences.clone();
+    assert!(!precedences.is_empty());
+    check_operators(&dataset, |individual| {
+      let mut positions = vec![0; dataset.size];
+      for (position, &node) in individual.nodes.iter().enumerate() {
+        positions[node] = position;
+      }
+      for &(before, after) in precedences.iter() {
+        assert!(positions[before] < positions[after], "{} visited after {} by {:?}", before, after, individual.nodes);
+      }
+    });
+  }
+}
@@ -0,0 +1,74 @@
+use tsp_solver::csv::{parse,CsvFile};
+
+fn labels(file: &CsvFile) -> Vec<&str> {
+  file.labels.iter().map(String::as_str).collect()
+}
+
+#[test]
+fn locations_with_a_header() {
+  let file = parse("label,x,y\nPar,2.35,48.85\nLyo,4.83,45.76\n", ',');
+  assert_eq!(labels(&file), vec!["Par", "Lyo"]);
+  assert_eq!(file.locations, vec![(2.35, 48.85), (4.83, 45.76)]);
+  assert!(file.distance_matrix.is_empty());
+}
+
+#[test]
+fn locations_without_a_header() {
+  let file = parse("1,0,0\n2,3,4\n3,6,8\n", ',');
+  assert_eq!(labels(&file), vec!["1", "2", "3"]);
+  assert_eq!(file.locations, vec![(0.0, 0.0), (3.0, 4.0), (6.0, 8.0)]);
+}
+
+#[test]
+fn matrix_with_a_corner_cell() {
+  let file = parse(",Par,Lyo,Mar\nPar,0,466,775\nLyo,466,0,314\nMar,775,,0\n", ',');
+  assert_eq!(labels(&file), vec!["Par", "Lyo", "Mar"]);
+  assert!(file.locations.is_empty());
+  assert_eq!(file.distance_matrix, vec![
+    vec![Some(0.0), Some(466.0), Some(775.0)],
+    vec![Some(466.0), Some(0.0), Some(314.0)],
+    vec![Some(775.0), None, Some(0.0)]
+  ]);
+}
+
+#[test]
+fn matrix_without_a_corner_cell() {
+  let file = parse("Par,Lyo\nPar,0,466\nLyo,466,0\n", ',');
+  assert_eq!(labels(&file), vec!["Par", "Lyo"]);
+  assert_eq!(file.distance_matrix, vec![vec![Some(0.0), Some(466.0)], vec![Some(466.0), Some(0.0)]]);
+}
+
+#[test]
+fn matrix_with_numeric_labels() {
+  let file = parse(",1,2,3\n1,0,5,7\n2,5,0,9\n3,7,9,0\n", ',');
+  assert_eq!(labels(&file), vec!["1", "2", "3"]);
+  assert!(file.locations.is_empty());
+  assert_eq!(file.distance_matrix[2], vec![Some(7.0), Some(9.0), Some(0.0)]);
+
+  let file = parse("1,2\n1,0,5\n2,5,0\n", ',');
+  assert_eq!(labels(&file), vec!["1", "2"]);
+  assert_eq!(file.distance_matrix, vec![vec![Some(0.0), Some(5.0)], vec![Some(5.0), Some(0.0)]]);
+}
+
+#[test]
+fn other_delimiters_and_quoted_fields() {
+  let file = parse("label;x;y\n\"Saint-Denis; Réunion\";55.45;-20.88\nPar;2.35;48.85\n", ';');
+  assert_eq!(labels(&file), vec!["Saint-Denis; Réunion", "Par"]);
+  assert_eq!(file.locations, vec![(55.45, -20.88), (2.35, 48.85)]);
+
+  let file = parse("\t\"A \"\"B\"\"\"\tC\n\"A \"\"B\"\"\"\t0\t1\nC\t1\t0\n", '\t');
+  assert_eq!(labels(&file), vec!["A \"B\"", "C"]);
+  assert_eq!(file.distance_matrix, vec![vec![Some(0.0), Some(1.0)], vec![Some(1.0), Some(0.0)]]);
+}
+
+#[test]
+#[should_panic(expected = "Line 3 of the dataset file should contain a label and 2 distances, found 2 fields")]
+fn matrix_lines_have_a_distance_per_node() {
+  parse(",Par,Lyo\nPar,0,466\nLyo,466\n", ',');
+}
+
+#[test]
+#[should_panic(expected = "Unable to parse the number abc on line 2 of the dataset file")]
+fn coordinates_are_numbers() {
+  parse("label,x,y\nPar,abc,48.85\n", ',');
+}
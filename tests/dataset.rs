@@ -0,0 +1,61 @@
+use rand::prelude::*;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix};
+use tsp_solver::storage::{euclidean_distance,Layout,Precision,Rounding};
+
+fn load_demo() -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/demo/demo.json", env!("CARGO_MANIFEST_DIR")), &DatasetOptions::default())
+}
+
+#[test]
+fn nodes_are_found_by_their_label() {
+  let dataset = load_demo();
+  for (node, label) in dataset.labels().iter().enumerate() {
+    assert_eq!(dataset.node(label), Ok(node));
+    assert_eq!(dataset.label_index().get(label), Some(node));
+  }
+  assert_eq!(dataset.node("Ber"), Err(String::from("unknown label Ber")));
+}
+
+#[test]
+fn distances_are_found_by_labels() {
+  let dataset = load_demo();
+  assert_eq!(dataset.distance_between("Par", "Bez"), Ok(123.0));
+  assert_eq!(dataset.distance_between("Nic", "Nar"), Ok(7567.0));
+  assert!(dataset.distance_between("Par", "Ber").is_err());
+}
+
+#[test]
+fn tours_are_converted_between_labels_and_nodes() {
+  let dataset = load_demo();
+  let labels: Vec<String> = ["Tou", "Par", "Nar"].iter().map(|label| label.to_string()).collect();
+  let nodes = dataset.nodes_of(&labels).expect("Every label should be known");
+  assert_eq!(nodes, vec![3, 0, 6]);
+  assert_eq!(dataset.labels_of(&nodes), labels);
+
+  let labels: Vec<String> = ["Par", "Ber", "Rom"].iter().map(|label| label.to_string()).collect();
+  assert_eq!(dataset.nodes_of(&labels), Err(String::from("unknown label Ber")));
+}
+
+#[test]
+fn neighbors_are_the_nearest_nodes_of_a_full_sort() {
+  let mut rng = StdRng::seed_from_u64(42);
+  let size = 500;
+  let locations: Vec<(f64, f64)> = (0..size).map(|_| (rng.gen_range(0.0..1000.0), rng.gen_range(0.0..1000.0))).collect();
+  let labels: Vec<String> = (0..size).map(|node| format!("N{}", node)).collect();
+  let rows: Vec<Vec<f64>> = locations.iter().map(|&l1| locations.iter().map(|&l2| euclidean_distance(l1, l2)).collect()).collect();
+
+  for candidates_count in [1, 7, 30] {
+    let options = DatasetOptions { candidates_count, ..DatasetOptions::default() };
+    // locations use the k-d tree, distance matrices a partial selection
+    let from_locations = Dataset::from_matrix(labels.clone(), Matrix::Locations(locations.clone(), Rounding::None), &options);
+    let from_rows = Dataset::from_matrix(labels.clone(), Matrix::from_rows(&rows, Precision::F64, Layout::Auto, Rounding::None), &options);
+
+    for (node, row) in rows.iter().enumerate() {
+      let mut sorted: Vec<usize> = (0..size).filter(|&neighbor| neighbor != node).collect();
+      sorted.sort_by(|&n1, &n2| row[n1].partial_cmp(&row[n2]).expect("Error while sorting neighbors"));
+      sorted.truncate(candidates_count);
+      assert_eq!(from_locations.nodes_neighbors[node], sorted);
+      assert_eq!(from_rows.nodes_neighbors[node], sorted);
+    }
+  }
+}
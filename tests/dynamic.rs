@@ -0,0 +1,91 @@
+use tsp_solver::dataset::{Dataset,DatasetOptions};
+use tsp_solver::dynamic::{insert_node,remove_node,repair_tour,NewNode};
+use tsp_solver::storage::Rounding;
+
+fn load(file_name: &str, options: &DatasetOptions) -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file_name), options)
+}
+
+#[test]
+fn inserted_locations_keep_the_rounding_of_the_dataset() {
+  let options = DatasetOptions { rounding: Rounding::Att, ..DatasetOptions::default() };
+  let dataset = load("att48/att48_locations.json", &options);
+
+  // the options used for the update don't round the distances
+  let updated = insert_node(&dataset, "new", NewNode::Location(5000.0, 3000.0), &DatasetOptions::default());
+  assert!(updated.rounding == Rounding::Att);
+  assert_eq!(updated.size, dataset.size + 1);
+  assert_eq!(updated.labels()[dataset.size], "new");
+  for node1 in 0..dataset.size {
+    for node2 in 0..dataset.size {
+      assert_eq!(updated.distance_matrix.get(node1, node2), dataset.distance_matrix.get(node1, node2));
+    }
+    let distance = updated.distance_matrix.get(node1, dataset.size);
+    assert_eq!(distance, distance.round());
+  }
+
+  // the rounding is kept through successive updates
+  let updated = remove_node(&updated, &dataset.labels()[0], &DatasetOptions::default());
+  assert!(updated.rounding == Rounding::Att);
+  assert_eq!(updated.distance_matrix.get(0, 1), dataset.distance_matrix.get(1, 2));
+}
+
+#[test]
+fn inserted_distances_extend_the_matrix() {
+  let dataset = load("demo/demo.json", &DatasetOptions::default());
+  let from: Vec<f64> = (1..=dataset.size).map(|node| node as f64 * 10.0).collect();
+  let to: Vec<f64> = (1..=dataset.size).map(|node| node as f64 * 20.0).collect();
+  let updated = insert_node(&dataset, "Ren", NewNode::Distances { from, to }, &DatasetOptions::default());
+
+  let new = updated.node("Ren").expect("The node should have been inserted");
+  assert_eq!(new, dataset.size);
+  for node in 0..dataset.size {
+    assert_eq!(updated.distance_matrix.get(new, node), (node + 1) as f64 * 10.0);
+    assert_eq!(updated.distance_matrix.get(node, new), (node + 1) as f64 * 20.0);
+  }
+  assert_eq!(updated.distance_matrix.get(new, new), 0.0);
+}
+
+#[test]
+#[should_panic(expected = "should have one distance from and to each of the 7 nodes")]
+fn inserted_distances_cover_every_node() {
+  let dataset = load("demo/demo.json", &DatasetOptions::default());
+  insert_node(&dataset, "Ren", NewNode::Distances { from: vec![1.0; 3], to: vec![1.0; 3] }, &DatasetOptions::default());
+}
+
+#[test]
+fn removed_nodes_shift_the_following_ones() {
+  let dataset = load("demo/demo.json", &DatasetOptions::default());
+  let updated = remove_node(&dataset, "Lyo", &DatasetOptions::default());
+  let lyo = dataset.node("Lyo").expect("Lyo should be a node of the demo");
+
+  assert_eq!(updated.size, dataset.size - 1);
+  assert!(updated.node("Lyo").is_err());
+  let kept: Vec<usize> = (0..dataset.size).filter(|&node| node != lyo).collect();
+  for (new1, &old1) in kept.iter().enumerate() {
+    assert_eq!(updated.labels()[new1], dataset.labels()[old1]);
+    for (new2, &old2) in kept.iter().enumerate() {
+      assert_eq!(updated.distance_matrix.get(new1, new2), dataset.distance_matrix.get(old1, old2));
+    }
+  }
+}
+
+#[test]
+fn repaired_tours_splice_out_removed_nodes_and_insert_new_ones() {
+  let dataset = load("demo/demo.json", &DatasetOptions::default());
+  let tour: Vec<usize> = (0..dataset.size).collect();
+
+  // the removed node is spliced out, the others keeping their order
+  let removed = remove_node(&dataset, "Lyo", &DatasetOptions::default());
+  let repaired = repair_tour(&dataset, &removed, &tour);
+  let expected: Vec<String> = dataset.labels().iter().filter(|label| label.as_str() != "Lyo").cloned().collect();
+  assert_eq!(removed.labels_of(&repaired), expected);
+
+  // a node next to Par only (and far from the others) is inserted at the start of the tour
+  let from: Vec<f64> = dataset.labels().iter().map(|label| if label == "Par" { 1.0 } else { 10_000.0 }).collect();
+  let inserted = insert_node(&dataset, "Ver", NewNode::Distances { from: from.clone(), to: from }, &DatasetOptions::default());
+  let repaired = repair_tour(&dataset, &inserted, &tour);
+  assert_eq!(repaired.len(), inserted.size);
+  assert_eq!(inserted.labels()[repaired[0]], "Ver");
+  assert_eq!(&repaired[1..], tour.as_slice());
+}
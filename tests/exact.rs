@@ -0,0 +1,120 @@
+use rand::prelude::*;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix};
+use tsp_solver::exact::{self,branch_and_bound,held_karp,TourKind,DEFAULT_NODE_LIMIT};
+use tsp_solver::storage::{Layout,Precision,Rounding};
+use tsp_solver::tours::TourEvaluation;
+
+// build a dataset from a random distance matrix, symmetric or not
+fn random_dataset(size: usize, symmetric: bool, rng: &mut StdRng) -> Dataset {
+  let draws: Vec<Vec<f64>> = (0..size)
+    .map(|node1| (0..size).map(|node2| if node1 == node2 { 0.0 } else { rng.gen_range(1..100) as f64 }).collect())
+    .collect();
+  // a symmetric matrix keeps the distances drawn above its diagonal
+  let rows: Vec<Vec<f64>> = (0..size)
+    .map(|node1| (0..size).map(|node2| if symmetric { draws[node1.min(node2)][node1.max(node2)] } else { draws[node1][node2] }).collect())
+    .collect();
+  let labels: Vec<String> = (0..size).map(|node| format!("N{}", node)).collect();
+  let matrix = Matrix::from_rows(&rows, Precision::F64, Layout::Auto, Rounding::None);
+  Dataset::from_matrix(labels, matrix, &DatasetOptions::default())
+}
+
+// length of a tour, going back to its first node for closed tours
+fn length(dataset: &Dataset, nodes: &[usize], kind: TourKind) -> f64 {
+  let path: f64 = nodes.windows(2).map(|edge| dataset.distance_matrix.get(edge[0], edge[1])).sum();
+  match kind {
+    TourKind::Open => path,
+    TourKind::Closed => path + dataset.distance_matrix.get(nodes[nodes.len() - 1], nodes[0])
+  }
+}
+
+// length of the best tour, by enumerating every permutation of the nodes (Heap's algorithm)
+fn brute_force(dataset: &Dataset, kind: TourKind) -> f64 {
+  let mut nodes: Vec<usize> = (0..dataset.size).collect();
+  let mut counters = vec![0; nodes.len()];
+  let mut best = length(dataset, &nodes, kind);
+  let mut index = 0;
+  while index < nodes.len() {
+    if counters[index] < index {
+      nodes.swap(if index % 2 == 0 { 0 } else { counters[index] }, index);
+      best = best.min(length(dataset, &nodes, kind));
+      counters[index] += 1;
+      index = 0;
+    } else {
+      counters[index] = 0;
+      index += 1;
+    }
+  }
+  best
+}
+
+// verify that both exact solvers find the optimal length, and that their tours have this length
+fn compare_solvers(symmetric: bool, kind: TourKind) {
+  let mut rng = StdRng::seed_from_u64(42);
+  for size in 2..=8 {
+    for _ in 0..5 {
+      let dataset = random_dataset(size, symmetric, &mut rng);
+      let optimum = brute_force(&dataset, kind);
+      for solution in [held_karp(&dataset, kind), branch_and_bound(&dataset, kind, DEFAULT_NODE_LIMIT)] {
+        assert!(solution.optimal);
+        assert_eq!(solution.length, optimum);
+        let mut nodes = solution.nodes.clone();
+        assert_eq!(length(&dataset, &nodes, kind), optimum);
+        nodes.sort_unstable();
+        assert_eq!(nodes, (0..size).collect::<Vec<usize>>());
+      }
+    }
+  }
+}
+
+#[test]
+fn symmetric_open_tours() {
+  compare_solvers(true, TourKind::Open);
+}
+
+#[test]
+fn symmetric_closed_tours() {
+  compare_solvers(true, TourKind::Closed);
+}
+
+#[test]
+fn asymmetric_open_tours() {
+  compare_solvers(false, TourKind::Open);
+}
+
+#[test]
+fn asymmetric_closed_tours() {
+  compare_solvers(false, TourKind::Closed);
+}
+
+#[test]
+fn node_limit_keeps_the_best_tour_found() {
+  let mut rng = StdRng::seed_from_u64(7);
+  let dataset = random_dataset(12, true, &mut rng);
+  let solution = branch_and_bound(&dataset, TourKind::Closed, 1);
+  assert!(!solution.optimal);
+  assert_eq!(solution.nodes.len(), 12);
+  assert_eq!(length(&dataset, &solution.nodes, TourKind::Closed), solution.length);
+  assert!(solution.length >= held_karp(&dataset, TourKind::Closed).length);
+}
+
+#[test]
+fn att48_closed_optimum_with_rounded_euclidean_distances() {
+  // att48.json stores the euclidean distances between the original coordinates, rounded to the nearest integer
+  let dataset = Dataset::from_file(&format!("{}/datasets/att48/att48.json", env!("CARGO_MANIFEST_DIR")), &DatasetOptions::default());
+
+  // optimal tour of the TSPLIB (att48.opt.tour), the cities 1 to 48 being labeled AA to BV
+  let tour = [1, 8, 38, 31, 44, 18, 7, 28, 6, 37, 19, 27, 17, 43, 30, 36, 46, 33, 20, 47, 21, 32, 39, 48, 5, 42, 24, 10, 45, 35, 4, 26, 2, 29, 34, 41, 16, 22, 3, 23, 14, 25, 13, 11, 12, 15, 40, 9];
+  let labels: Vec<String> = tour.iter().map(|city| format!("{}{}", (b'A' + ((city - 1) / 26) as u8) as char, (b'A' + ((city - 1) % 26) as u8) as char)).collect();
+  let evaluation = TourEvaluation::new(&dataset, &labels);
+  assert!(evaluation.is_valid());
+  // the evaluation measures the open tour, the closed one goes back to the first city
+  let open_length = evaluation.individual.expect("The tour should be evaluated").length;
+  assert_eq!(open_length, 33088.0);
+  let nodes = dataset.nodes_of(&labels).expect("Unable to find the nodes of the tour");
+  assert_eq!(length(&dataset, &nodes, TourKind::Closed), 33551.0);
+
+  // the exact solver proves that this tour is still optimal with these distances
+  let solution = exact::solve(&dataset, TourKind::Closed, DEFAULT_NODE_LIMIT);
+  assert!(solution.optimal);
+  assert_eq!(solution.length, 33551.0);
+}
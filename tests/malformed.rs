@@ -0,0 +1,56 @@
+use tsp_solver::dataset::{Dataset,DatasetOptions};
+use tsp_solver::storage::Rounding;
+
+// load a dataset of the malformed directory, which should panic
+fn load(file_name: &str) -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/malformed/{}", env!("CARGO_MANIFEST_DIR"), file_name), &DatasetOptions::default())
+}
+
+#[test]
+#[should_panic(expected = "row 2 has 3 distances, but there are 4 rows")]
+fn ragged_matrix() {
+  load("ragged.json");
+}
+
+#[test]
+#[should_panic(expected = "Par can't be followed by both Mar and Tou")]
+fn branching_fixed_edges() {
+  load("branching.json");
+}
+
+#[test]
+#[should_panic(expected = "they form a cycle going through Par")]
+fn cycle_of_fixed_edges() {
+  load("cycle.json");
+}
+
+#[test]
+#[should_panic(expected = "there is no path visiting every node using only allowed edges")]
+fn infeasible_forbidden_edges() {
+  load("infeasible.json");
+}
+
+#[test]
+#[should_panic(expected = "every edge from and to Tou is forbidden")]
+fn isolated_node() {
+  load("isolated.json");
+}
+
+#[test]
+#[should_panic(expected = "The precedences form a cycle : Par can never be visited")]
+fn cycle_of_precedences() {
+  load("precedences_cycle.json");
+}
+
+#[test]
+#[should_panic(expected = "Prizes can't be used with precedences")]
+fn prizes_with_precedences() {
+  load("prizes_precedences.json");
+}
+
+#[test]
+#[should_panic(expected = "The ATT rounding can only be used with datasets made of locations")]
+fn att_rounding_of_a_distance_matrix() {
+  let options = DatasetOptions { rounding: Rounding::Att, ..DatasetOptions::default() };
+  Dataset::from_file(&format!("{}/datasets/demo/demo.json", env!("CARGO_MANIFEST_DIR")), &options);
+}
@@ -0,0 +1,100 @@
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix};
+use tsp_solver::metric::MetricReport;
+use tsp_solver::storage::{CacheMode,Layout,Precision,Rounding};
+
+// build a dataset without constraints nor variants
+fn build(matrix: Matrix, options: &DatasetOptions) -> Dataset {
+  let labels: Vec<String> = (0..matrix.len()).map(|node| format!("N{}", node)).collect();
+  Dataset::from_matrix(labels, matrix, options)
+}
+
+// build a dataset from the rows of a distance matrix
+fn from_rows(rows: &[Vec<f64>], precision: Precision) -> Dataset {
+  build(Matrix::from_rows(rows, precision, Layout::Auto, Rounding::None), &DatasetOptions::default())
+}
+
+fn report(dataset: &Dataset) -> MetricReport<'_> {
+  MetricReport::new(dataset, &mut rand::thread_rng())
+}
+
+#[test]
+fn triangle_violations_are_counted() {
+  // going from N0 to N2 through N1 is shorter than the direct edge, in both directions
+  let dataset = from_rows(&[vec![0.0, 1.0, 10.0], vec![1.0, 0.0, 1.0], vec![10.0, 1.0, 0.0]], Precision::F64);
+  let report = report(&dataset);
+  assert!(!report.euclidean);
+  assert!(!report.sampled);
+  assert_eq!(report.checked_triples, 6);
+  assert_eq!(report.triangle_violations, 2);
+  let (i, j, k, excess) = report.worst_violation.expect("There should be a violation");
+  assert_eq!((i.min(k), j, i.max(k), excess), (0, 1, 2, 8.0));
+  assert_eq!(report.asymmetric_pairs, 0);
+  assert!(report.to_string().contains("2 violations out of 6 triples"));
+}
+
+#[test]
+fn asymmetric_pairs_are_measured() {
+  let dataset = from_rows(&[vec![0.0, 2.0, 3.0], vec![5.0, 0.0, 3.0], vec![3.0, 4.0, 0.0]], Precision::F64);
+  let report = report(&dataset);
+  assert_eq!(report.checked_pairs, 3);
+  assert_eq!(report.asymmetric_pairs, 2);
+  assert_eq!(report.mean_asymmetry, 2.0);
+  assert_eq!(report.worst_asymmetry, Some((1, 0, 3.0)));
+}
+
+#[test]
+fn forbidden_edges_are_not_checked() {
+  let dataset = from_rows(&[vec![0.0, 1.0, f64::INFINITY], vec![1.0, 0.0, 1.0], vec![f64::INFINITY, 1.0, 0.0]], Precision::F64);
+  let report = report(&dataset);
+  // every triple of 3 nodes uses the forbidden edge
+  assert_eq!(report.checked_triples, 0);
+  assert_eq!(report.checked_pairs, 2);
+  assert!(report.to_string().contains("not enough data"));
+}
+
+#[test]
+fn f32_rounding_errors_are_not_violations() {
+  // 0.1 + 0.2 is slightly more than 0.3 in f64, but the f32 values of 0.1 and 0.2 sum to slightly less than the f32 value of 0.3
+  let dataset = from_rows(&[vec![0.0, 0.1, 0.3], vec![0.1, 0.0, 0.2], vec![0.3, 0.2, 0.0]], Precision::F32);
+  let report = report(&dataset);
+  assert_eq!(report.checked_triples, 6);
+  assert_eq!(report.triangle_violations, 0);
+}
+
+#[test]
+fn euclidean_locations_are_not_checked_whatever_their_cache() {
+  let locations = vec![(0.0, 0.0), (3.0, 4.0), (6.0, 0.0), (1.0, 7.0)];
+  for cache in [CacheMode::Auto, CacheMode::None, CacheMode::Full, CacheMode::Neighbors] {
+    for precision in [Precision::F64, Precision::F32] {
+      let options = DatasetOptions { cache, precision, ..DatasetOptions::default() };
+      let dataset = build(Matrix::Locations(locations.clone(), Rounding::None), &options);
+      let report = report(&dataset);
+      assert!(report.euclidean);
+      assert_eq!(report.checked_triples, 0);
+    }
+  }
+
+  // rounded distances can break the triangle inequality, so they are checked
+  let dataset = build(Matrix::Locations(locations, Rounding::Nint), &DatasetOptions::default());
+  let report = report(&dataset);
+  assert!(!report.euclidean);
+  assert_eq!(report.checked_triples, 24);
+}
+
+#[test]
+#[should_panic(expected = "The distance from N1 to N0 (row 1, column 0) should not be negative, found -1")]
+fn negative_distances_are_rejected() {
+  from_rows(&[vec![0.0, 1.0], vec![-1.0, 0.0]], Precision::F64);
+}
+
+#[test]
+#[should_panic(expected = "The distance from N1 to itself (row 1, column 1) should be 0, found 2")]
+fn distances_to_itself_should_be_zero() {
+  from_rows(&[vec![0.0, 1.0], vec![1.0, 2.0]], Precision::F64);
+}
+
+#[test]
+#[should_panic(expected = "The location of N1 (index 1) should have finite coordinates, found (NaN, 0)")]
+fn locations_should_be_finite() {
+  build(Matrix::Locations(vec![(0.0, 0.0), (f64::NAN, 0.0)], Rounding::None), &DatasetOptions::default());
+}
@@ -0,0 +1,93 @@
+use rand::prelude::*;
+use tsp_solver::constraints::Constraints;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix,Variants};
+use tsp_solver::generation::Generation;
+use tsp_solver::individual::Individual;
+use tsp_solver::orienteering::Orienteering;
+use tsp_solver::storage::Rounding;
+
+fn load(file_name: &str) -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file_name), &DatasetOptions::default())
+}
+
+// verify that the individual is a permutation of the nodes, starting from the depot and fitting in the budget
+fn check_individual(dataset: &Dataset, individual: &Individual) {
+  let orienteering = dataset.orienteering.as_ref().expect("The dataset should have prizes");
+  let mut sorted_nodes = individual.nodes.clone();
+  sorted_nodes.sort_unstable();
+  assert_eq!(sorted_nodes, (0..dataset.size).collect::<Vec<usize>>());
+
+  assert!(individual.visited >= 1 && individual.visited <= dataset.size);
+  assert_eq!(individual.nodes[0], orienteering.depot);
+  let length = orienteering.length(&dataset.distance_matrix, &individual.nodes[..individual.visited]);
+  assert!(length <= orienteering.budget, "tour of length {} over the budget {}", length, orienteering.budget);
+}
+
+#[test]
+fn repaired_tours_fit_in_the_budget() {
+  let dataset = load("demo/demo_prizes.json");
+  let mut rng = StdRng::seed_from_u64(42);
+  for _ in 0..500 {
+    // any number of visited nodes, the depot being visited or not
+    let mut nodes: Vec<usize> = (0..dataset.size).collect();
+    nodes.shuffle(&mut rng);
+    let visited = rng.gen_range(0..=dataset.size);
+    check_individual(&dataset, &Individual::from_visited_nodes(&dataset, nodes, visited));
+  }
+}
+
+#[test]
+fn repair_only_skips_nodes_over_the_budget() {
+  let dataset = load("demo/demo_prizes.json");
+  let orienteering = dataset.orienteering.as_ref().expect("The dataset should have prizes");
+  let mut rng = StdRng::seed_from_u64(7);
+  for _ in 0..500 {
+    let mut nodes: Vec<usize> = (0..dataset.size).collect();
+    nodes.shuffle(&mut rng);
+    let depot_position = nodes.iter().position(|&node| node == orienteering.depot).expect("Unable to find the depot");
+    nodes.swap(0, depot_position);
+    let visited = rng.gen_range(1..=dataset.size);
+
+    // a tour already in the budget is kept as it is
+    let individual = Individual::from_visited_nodes(&dataset, nodes.clone(), visited);
+    if orienteering.length(&dataset.distance_matrix, &nodes[..visited]) <= orienteering.budget {
+      assert_eq!(individual.visited, visited);
+      assert_eq!(individual.nodes, nodes);
+    } else {
+      assert!(individual.visited < visited);
+    }
+  }
+}
+
+#[test]
+fn depot_alone_is_visited_when_no_node_fits_in_the_budget() {
+  // every other node is at least 10 away from the depot, so no round trip fits in a budget of 5
+  let locations = vec![(0.0, 0.0), (10.0, 0.0), (0.0, 10.0), (10.0, 10.0)];
+  let labels: Vec<String> = (0..locations.len()).map(|node| format!("N{}", node)).collect();
+  let constraints = Constraints::none(&labels);
+  let orienteering = Orienteering::new(&labels, 0, vec![0.0, 1.0, 1.0, 1.0], 5.0);
+  let variants = Variants { orienteering: Some(orienteering), ..Variants::default() };
+  let dataset = Dataset::new(labels, Matrix::Locations(locations, Rounding::None), constraints, variants, &DatasetOptions::default());
+
+  let individual = Individual::from_visited_nodes(&dataset, vec![3, 2, 1, 0], 4);
+  assert_eq!((individual.nodes[0], individual.visited), (0, 1));
+  check_individual(&dataset, &individual);
+  let mut generation = Generation::new(0, 5, 10, 2, &dataset, &mut rand::thread_rng());
+  for _ in 0..5 {
+    assert!(generation.population.iter().all(|individual| individual.visited == 1 && individual.length == 0.0));
+    generation = generation.evolve(&mut rand::thread_rng(), 4, 10);
+  }
+}
+
+#[test]
+fn evolved_tours_fit_in_the_budget() {
+  let dataset = load("demo/demo_prizes.json");
+  let mut rng = rand::thread_rng();
+  let mut generation = Generation::new(0, 30, 50, 5, &dataset, &mut rng);
+  for _ in 0..30 {
+    for individual in generation.population.iter() {
+      check_individual(&dataset, individual);
+    }
+    generation = generation.evolve(&mut rng, 4, 10);
+  }
+}
@@ -0,0 +1,93 @@
+use tsp_solver::dataset::{Dataset,DatasetOptions};
+use tsp_solver::generation::Generation;
+use tsp_solver::pareto::{crowded_order,crowding_distances,dominates,non_dominated_sort,ParetoFront};
+
+// tolerance on the crowding distances, which sum normalized differences
+const EPSILON: f64 = 1e-9;
+
+// cost vectors with three fronts : A, B, C and G don't dominate each other,
+// D is dominated by B and G, E by C, and F by every other one
+fn objectives() -> Vec<Vec<f64>> {
+  vec![
+    vec![1.0, 5.0], // A
+    vec![2.0, 3.0], // B
+    vec![4.0, 1.0], // C
+    vec![3.0, 4.0], // D
+    vec![5.0, 2.0], // E
+    vec![6.0, 6.0], // F
+    vec![3.0, 2.5]  // G
+  ]
+}
+
+fn sorted(mut indexes: Vec<usize>) -> Vec<usize> {
+  indexes.sort_unstable();
+  indexes
+}
+
+#[test]
+fn domination_needs_one_strictly_better_objective() {
+  assert!(dominates(&[1.0, 2.0], &[1.0, 3.0]));
+  assert!(dominates(&[1.0, 2.0], &[2.0, 3.0]));
+  assert!(!dominates(&[1.0, 2.0], &[1.0, 2.0]));
+  assert!(!dominates(&[1.0, 3.0], &[2.0, 2.0]));
+  assert!(!dominates(&[1.0, 3.0], &[1.0, 2.0]));
+}
+
+#[test]
+fn fronts_are_ranked_by_domination() {
+  let fronts: Vec<Vec<usize>> = non_dominated_sort(&objectives()).into_iter().map(sorted).collect();
+  assert_eq!(fronts, vec![vec![0, 1, 2, 6], vec![3, 4], vec![5]]);
+
+  // identical cost vectors don't dominate each other, so they share their front
+  let fronts: Vec<Vec<usize>> = non_dominated_sort(&[vec![1.0, 1.0], vec![2.0, 2.0], vec![1.0, 1.0]]).into_iter().map(sorted).collect();
+  assert_eq!(fronts, vec![vec![0, 2], vec![1]]);
+  assert!(non_dominated_sort(&[]).is_empty());
+}
+
+#[test]
+fn crowding_distances_favor_the_ends_and_isolated_vectors() {
+  let objectives = objectives();
+  let distances = crowding_distances(&objectives, &[0, 1, 2, 6]);
+  // the first costs of the front range from 1 (A) to 4 (C), and the second ones from 1 (C) to 5 (A)
+  // B lies between A and G : (3 - 1) / 3 + (5 - 2.5) / 4, G lies between B and C : (4 - 2) / 3 + (3 - 1) / 4
+  assert_eq!(distances[0], f64::INFINITY);
+  assert_eq!(distances[2], f64::INFINITY);
+  assert!((distances[1] - (2.0 / 3.0 + 0.625)).abs() < EPSILON, "crowding distance of B : {}", distances[1]);
+  assert!((distances[3] - (2.0 / 3.0 + 0.5)).abs() < EPSILON, "crowding distance of G : {}", distances[3]);
+
+  // fronts of one or two vectors only have ends
+  assert_eq!(crowding_distances(&objectives, &[3, 4]), vec![f64::INFINITY; 2]);
+  assert_eq!(crowding_distances(&objectives, &[5]), vec![f64::INFINITY]);
+}
+
+#[test]
+fn crowded_order_sorts_by_front_then_by_decreasing_crowding_distance() {
+  let order = crowded_order(&objectives());
+  assert_eq!(order.len(), 7);
+  // the ends A and C first, then B which is less crowded than G, then the second front and F
+  assert_eq!(sorted(order[..2].to_vec()), vec![0, 2]);
+  assert_eq!(order[2..4], [1, 6]);
+  assert_eq!(sorted(order[4..6].to_vec()), vec![3, 4]);
+  assert_eq!(order[6], 5);
+}
+
+#[test]
+fn evolved_populations_start_with_non_dominated_tours() {
+  let dataset = Dataset::from_file(&format!("{}/datasets/demo/demo_costs.json", env!("CARGO_MANIFEST_DIR")), &DatasetOptions::default());
+  let mut rng = rand::thread_rng();
+  let mut generation = Generation::new(0, 20, 30, 5, &dataset, &mut rng);
+  for _ in 0..20 {
+    generation = generation.evolve(&mut rng, 4, 10);
+    assert_eq!(generation.population.len(), 30);
+    let objectives: Vec<Vec<f64>> = generation.population.iter().map(|individual| individual.objectives()).collect();
+    assert!(objectives.iter().all(|other| !dominates(other, &objectives[0])));
+
+    // the pareto front is made of distinct non-dominated cost vectors
+    let front = ParetoFront::new(&dataset, &generation.population);
+    assert!(!front.individuals.is_empty());
+    for individual in front.individuals.iter() {
+      assert!(objectives.iter().all(|other| !dominates(other, &individual.objectives())));
+    }
+    assert!(front.individuals.windows(2).all(|pair| pair[0].objectives() != pair[1].objectives()));
+  }
+}
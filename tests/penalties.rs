@@ -0,0 +1,74 @@
+use rand::prelude::*;
+use tsp_solver::constraints::Constraints;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix,Variants};
+use tsp_solver::generation::Generation;
+use tsp_solver::individual::Individual;
+use tsp_solver::penalties::NodeCosts;
+use tsp_solver::storage::Rounding;
+
+fn load(file_name: &str) -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file_name), &DatasetOptions::default())
+}
+
+// verify that the individual is a permutation of the nodes visiting at least one node and every mandatory one
+fn check_individual(dataset: &Dataset, individual: &Individual) {
+  let node_costs = dataset.node_costs.as_ref().expect("The dataset should have node costs");
+  let mut sorted_nodes = individual.nodes.clone();
+  sorted_nodes.sort_unstable();
+  assert_eq!(sorted_nodes, (0..dataset.size).collect::<Vec<usize>>());
+
+  assert!(individual.visited >= 1 && individual.visited <= dataset.size);
+  assert_eq!(node_costs.skipped_mandatory_nodes(&individual.nodes, individual.visited), Vec::<usize>::new());
+  assert!(individual.length.is_finite());
+}
+
+#[test]
+fn repaired_tours_visit_every_mandatory_node() {
+  let dataset = load("demo/demo_node_costs.json");
+  let mut rng = StdRng::seed_from_u64(42);
+  for _ in 0..500 {
+    let mut nodes: Vec<usize> = (0..dataset.size).collect();
+    nodes.shuffle(&mut rng);
+    let visited = rng.gen_range(0..=dataset.size);
+    let individual = Individual::from_visited_nodes(&dataset, nodes, visited);
+    check_individual(&dataset, &individual);
+    // the visited nodes stay visited, and only Mar, Tou and Nic can be skipped
+    assert!(individual.visited >= visited.max(4));
+  }
+}
+
+#[test]
+fn one_node_is_visited_when_every_node_is_optional() {
+  let locations = vec![(0.0, 0.0), (3.0, 4.0), (6.0, 0.0), (1.0, 7.0)];
+  let labels: Vec<String> = (0..locations.len()).map(|node| format!("N{}", node)).collect();
+  let constraints = Constraints::none(&labels);
+  let node_costs = NodeCosts::new(&labels, vec![1.0; 4], vec![Some(0.0); 4]);
+  let variants = Variants { node_costs, ..Variants::default() };
+  let dataset = Dataset::new(labels, Matrix::Locations(locations, Rounding::None), constraints, variants, &DatasetOptions::default());
+
+  // skipping every node is free, but a tour visits at least one node
+  let individual = Individual::from_visited_nodes(&dataset, vec![2, 0, 3, 1], 0);
+  assert_eq!((individual.nodes[0], individual.visited), (2, 1));
+  check_individual(&dataset, &individual);
+
+  let mut generation = Generation::new(0, 10, 20, 2, &dataset, &mut rand::thread_rng());
+  for _ in 0..10 {
+    for individual in generation.population.iter() {
+      check_individual(&dataset, individual);
+    }
+    generation = generation.evolve(&mut rand::thread_rng(), 4, 10);
+  }
+}
+
+#[test]
+fn evolved_tours_visit_every_mandatory_node() {
+  let dataset = load("demo/demo_node_costs.json");
+  let mut rng = rand::thread_rng();
+  let mut generation = Generation::new(0, 30, 50, 5, &dataset, &mut rng);
+  for _ in 0..30 {
+    for individual in generation.population.iter() {
+      check_individual(&dataset, individual);
+    }
+    generation = generation.evolve(&mut rng, 4, 10);
+  }
+}
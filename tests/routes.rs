@@ -0,0 +1,143 @@
+use rand::prelude::*;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix};
+use tsp_solver::routes::{Fleet,RoutesObjective,Vehicles};
+use tsp_solver::storage::Rounding;
+
+// load the P-n16-k8 instance of CVRPLIB : 15 customers, vehicles of capacity 35, optimal value 450 with 8 routes
+fn load_instance() -> Dataset {
+  Dataset::from_file(&format!("{}/datasets/cvrp/P-n16-k8.vrp", env!("CARGO_MANIFEST_DIR")), &DatasetOptions::default())
+}
+
+// returns the routes of a split, as lists of customers
+fn routes(customers: &[usize], starts: &[usize]) -> Vec<Vec<usize>> {
+  starts.iter().enumerate()
+    .map(|(index, &start)| customers[start..starts.get(index + 1).copied().unwrap_or(customers.len())].to_vec())
+    .collect()
+}
+
+#[test]
+fn split_finds_the_optimal_routes_of_the_optimal_giant_tour() {
+  let dataset = load_instance();
+  let fleet = dataset.fleet.as_ref().expect("The instance should have a fleet");
+  let optimal_routes = [["2", "4"].as_slice(), &["3"], &["5", "12"], &["6", "15"], &["7"], &["8", "10", "14"], &["9"], &["11", "13", "16"]];
+  let labels: Vec<String> = optimal_routes.iter().flat_map(|route| route.iter().map(|label| label.to_string())).collect();
+  let nodes = dataset.nodes_of(&labels).expect("Unknown label");
+
+  let (value, customers, starts) = fleet.split(&dataset.distance_matrix, &nodes);
+  assert_eq!(value, 450.0);
+  let routes = routes(&customers, &starts);
+  assert_eq!(routes.len(), 8);
+  for (route, optimal_route) in routes.iter().zip(optimal_routes.iter()) {
+    assert_eq!(&dataset.labels_of(route), optimal_route);
+  }
+}
+
+#[test]
+fn split_respects_the_capacity() {
+  let dataset = load_instance();
+  let fleet = dataset.fleet.as_ref().expect("The instance should have a fleet");
+  let capacity = match &fleet.vehicles {
+    Vehicles::Capacitated { capacity, .. } => *capacity,
+    Vehicles::Salesmen(_) => panic!("The vehicles should have a capacity")
+  };
+
+  let mut rng = StdRng::seed_from_u64(42);
+  let mut nodes: Vec<usize> = (0..dataset.size).collect();
+  for _ in 0..100 {
+    nodes.shuffle(&mut rng);
+    let (value, customers, starts) = fleet.split(&dataset.distance_matrix, &nodes);
+    assert_eq!(customers.len(), dataset.size - 1);
+
+    // the value is the total length of the routes, each one leaving from and going back to the depot
+    let mut total = 0.0;
+    for route in routes(&customers, &starts) {
+      assert!(fleet.load(&route).expect("The vehicles should have a capacity") <= capacity);
+      let path = [&[fleet.depot], route.as_slice(), &[fleet.depot]].concat();
+      total += path.windows(2).map(|edge| dataset.distance_matrix.get(edge[0], edge[1])).sum::<f64>();
+    }
+    assert_eq!(value, total);
+    assert!(value >= 450.0);
+  }
+}
+
+// returns the length of each route, leaving from and going back to the depot
+fn route_lengths(matrix: &Matrix, depot: usize, routes: &[Vec<usize>]) -> Vec<f64> {
+  routes.iter()
+    .map(|route| [&[depot], route.as_slice(), &[depot]].concat().windows(2).map(|edge| matrix.get(edge[0], edge[1])).sum())
+    .collect()
+}
+
+// returns the best value of every split of the customers into the given number of non-empty routes
+fn brute_force(matrix: &Matrix, depot: usize, customers: &[usize], salesmen: usize, objective: RoutesObjective) -> f64 {
+  // every subset of the positions 1..customers is a set of cuts
+  (0..1usize << (customers.len() - 1))
+    .filter(|cuts| cuts.count_ones() as usize == salesmen - 1)
+    .map(|cuts| {
+      let starts: Vec<usize> = [0].into_iter().chain((1..customers.len()).filter(|position| cuts & (1 << (position - 1)) != 0)).collect();
+      let lengths = route_lengths(matrix, depot, &routes(customers, &starts));
+      match objective {
+        RoutesObjective::MinSum => lengths.iter().sum(),
+        RoutesObjective::MinMax => lengths.iter().copied().fold(0.0, f64::max)
+      }
+    })
+    .fold(f64::INFINITY, f64::min)
+}
+
+#[test]
+fn min_max_split_balances_the_routes() {
+  // the depot is between A and B on one side and C and D on the other side of a line
+  let matrix = Matrix::Locations(vec![(0.0, 0.0), (-2.0, 0.0), (-1.0, 0.0), (1.0, 0.0), (2.5, 0.0)], Rounding::None);
+  let fleet = Fleet::new(0, 2, RoutesObjective::MinMax, 5);
+
+  // splitting after A gives routes of 4 and 7, after B of 4 and 5, after C of 6 and 5
+  let (value, customers, starts) = fleet.split(&matrix, &[0, 1, 2, 3, 4]);
+  assert_eq!(value, 5.0);
+  assert_eq!(routes(&customers, &starts), vec![vec![1, 2], vec![3, 4]]);
+
+  // three salesmen : the route going to D and back (5) can't be shortened
+  let fleet = Fleet::new(0, 3, RoutesObjective::MinMax, 5);
+  let (value, customers, starts) = fleet.split(&matrix, &[0, 1, 2, 3, 4]);
+  assert_eq!(value, 5.0);
+  assert_eq!(routes(&customers, &starts).len(), 3);
+  assert!(route_lengths(&matrix, 0, &routes(&customers, &starts)).iter().all(|&length| length <= 5.0));
+
+  // as many salesmen as customers : each one visits a single customer
+  let fleet = Fleet::new(0, 4, RoutesObjective::MinMax, 5);
+  let (value, customers, starts) = fleet.split(&matrix, &[0, 1, 2, 3, 4]);
+  assert_eq!(value, 5.0);
+  assert_eq!(starts, vec![0, 1, 2, 3]);
+  assert_eq!(customers, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn salesmen_splits_are_optimal() {
+  let mut rng = StdRng::seed_from_u64(42);
+  for _ in 0..50 {
+    let size = rng.gen_range(3..=10);
+    let locations: Vec<(f64, f64)> = (0..size).map(|_| (rng.gen_range(0..100) as f64, rng.gen_range(0..100) as f64)).collect();
+    let matrix = Matrix::Locations(locations, Rounding::None);
+    let mut nodes: Vec<usize> = (0..size).collect();
+    nodes.shuffle(&mut rng);
+    let depot = rng.gen_range(0..size);
+
+    for salesmen in 1..size {
+      for objective in [RoutesObjective::MinSum, RoutesObjective::MinMax] {
+        let fleet = Fleet::new(depot, salesmen, objective, size);
+        let (value, customers, starts) = fleet.split(&matrix, &nodes);
+        let optimum = brute_force(&matrix, depot, &customers, salesmen, objective);
+        assert!((value - optimum).abs() < 1e-9, "split value {} instead of {}", value, optimum);
+
+        // the value is the one of the returned routes, each salesman visiting at least one customer
+        let routes = routes(&customers, &starts);
+        assert_eq!(routes.len(), salesmen);
+        assert!(routes.iter().all(|route| !route.is_empty()));
+        let lengths = route_lengths(&matrix, depot, &routes);
+        let routes_value = match objective {
+          RoutesObjective::MinSum => lengths.iter().sum(),
+          RoutesObjective::MinMax => lengths.iter().copied().fold(0.0, f64::max)
+        };
+        assert!((value - routes_value).abs() < 1e-9);
+      }
+    }
+  }
+}
@@ -0,0 +1,90 @@
+use tsp_solver::constraints::Constraints;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix,Variants};
+use tsp_solver::generation::Generation;
+use tsp_solver::individual::Individual;
+use tsp_solver::schedule::{Schedule,TimeWindows,TimeWindowsMode,DEFAULT_LATENESS_PENALTY};
+use tsp_solver::storage::{Layout,Precision,Rounding};
+use tsp_solver::tours::TourEvaluation;
+
+// A, B, C and D, where B opens at 30 and closes at 40, and D closes at 50
+// A takes 5 to serve, B 10, C nothing and D 2
+fn time_windows_dataset(mode: TimeWindowsMode) -> Dataset {
+  let labels: Vec<String> = ["A", "B", "C", "D"].iter().map(|label| label.to_string()).collect();
+  let rows = vec![
+    vec![0.0, 10.0, 30.0, 35.0],
+    vec![10.0, 0.0, 20.0, 25.0],
+    vec![30.0, 20.0, 0.0, 5.0],
+    vec![35.0, 25.0, 5.0, 0.0]
+  ];
+  let windows = vec![None, Some((30.0, 40.0)), None, Some((0.0, 50.0))];
+  let time_windows = TimeWindows::new(&labels, windows, vec![5.0, 10.0, 0.0, 2.0], mode, DEFAULT_LATENESS_PENALTY);
+  let constraints = Constraints::none(&labels);
+  let variants = Variants { time_windows, ..Variants::default() };
+  Dataset::new(labels, Matrix::from_rows(&rows, Precision::F64, Layout::Auto, Rounding::None), constraints, variants, &DatasetOptions::default())
+}
+
+fn labels(labels: &[&str]) -> Vec<String> {
+  labels.iter().map(|label| label.to_string()).collect()
+}
+
+#[test]
+fn schedule_waits_for_the_windows_and_adds_the_service_times() {
+  let dataset = time_windows_dataset(TimeWindowsMode::Penalty);
+  let time_windows = dataset.time_windows.as_ref().expect("The dataset should have time windows");
+  let nodes = dataset.nodes_of(&labels(&["A", "B", "C", "D"])).expect("Unable to find the nodes");
+
+  // A is left at 5, B is reached at 15 and waits for its opening at 30, is left at 40,
+  // C is reached at 60, and D at 65, 15 after its closing
+  let schedule = Schedule::new(&dataset, time_windows, &nodes);
+  assert_eq!(schedule.stops, vec![(0, 0.0, 0.0, 0.0), (1, 15.0, 30.0, 0.0), (2, 60.0, 60.0, 0.0), (3, 65.0, 65.0, 15.0)]);
+  assert_eq!((schedule.total_lateness, schedule.late_stops), (15.0, 1));
+  assert!(schedule.to_string().contains("late stops : 1 · total lateness : 15.00"));
+
+  // B is reached at 67 after D, 27 late, then C at 97
+  let nodes = dataset.nodes_of(&labels(&["A", "D", "B", "C"])).expect("Unable to find the nodes");
+  assert_eq!(time_windows.lateness(&dataset.distance_matrix, &nodes), (27.0, 1));
+
+  // going through B late and D late adds both latenesses
+  let nodes = dataset.nodes_of(&labels(&["A", "C", "B", "D"])).expect("Unable to find the nodes");
+  assert_eq!(time_windows.lateness(&dataset.distance_matrix, &nodes), (15.0 + 40.0, 2));
+
+  // D, C then B arrives at 27 and waits for 30, every window being respected
+  let nodes = dataset.nodes_of(&labels(&["D", "C", "B", "A"])).expect("Unable to find the nodes");
+  let schedule = Schedule::new(&dataset, time_windows, &nodes);
+  assert_eq!(schedule.stops, vec![(3, 0.0, 0.0, 0.0), (2, 7.0, 7.0, 0.0), (1, 27.0, 30.0, 0.0), (0, 50.0, 50.0, 0.0)]);
+  assert_eq!((schedule.total_lateness, schedule.late_stops), (0.0, 0));
+  assert!(schedule.to_string().contains("every time window is respected"));
+}
+
+#[test]
+fn lateness_is_penalized_or_forbidden() {
+  // both tours are 35 long, but the first one reaches D 15 late
+  let (late, on_time) = (labels(&["A", "B", "C", "D"]), labels(&["D", "C", "B", "A"]));
+
+  let dataset = time_windows_dataset(TimeWindowsMode::Penalty);
+  let objective = |tour: &[String]| Individual::from_nodes(&dataset, dataset.nodes_of(tour).expect("Unable to find the nodes")).length;
+  assert_eq!(objective(&late), 35.0 + DEFAULT_LATENESS_PENALTY * 15.0);
+  assert_eq!(objective(&on_time), 35.0);
+  assert!(TourEvaluation::new(&dataset, &late).is_valid());
+
+  // with hard time windows, a late tour is worse than the longest possible tour on time, and isn't valid
+  let dataset = time_windows_dataset(TimeWindowsMode::Hard);
+  let objective = |tour: &[String]| Individual::from_nodes(&dataset, dataset.nodes_of(tour).expect("Unable to find the nodes")).length;
+  assert_eq!(objective(&late), dataset.longest_path_length + 35.0 + 15.0);
+  assert!(objective(&late) > dataset.longest_path_length);
+  assert_eq!(objective(&on_time), 35.0);
+  let evaluation = TourEvaluation::new(&dataset, &late);
+  assert_eq!(evaluation.violations, vec![String::from("1 stops are reached after the end of their time window")]);
+  assert!(TourEvaluation::new(&dataset, &on_time).is_valid());
+
+  // the genetic algorithm ends on a tour respecting every window
+  let mut rng = rand::thread_rng();
+  let mut generation = Generation::new(0, 10, 20, 2, &dataset, &mut rng);
+  for _ in 1..10 {
+    generation = generation.evolve(&mut rng, 4, 10);
+  }
+  let best = &generation.population[0];
+  let time_windows = dataset.time_windows.as_ref().expect("The dataset should have time windows");
+  assert_eq!(time_windows.lateness(&dataset.distance_matrix, &best.nodes), (0.0, 0));
+  assert!(best.length <= dataset.longest_path_length);
+}
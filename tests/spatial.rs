@@ -0,0 +1,74 @@
+use rand::prelude::*;
+use tsp_solver::spatial::KdTree;
+
+// squared distance between two locations, enough to compare distances
+fn squared_distance(l1: (f64, f64), l2: (f64, f64)) -> f64 {
+  (l2.0 - l1.0).powi(2) + (l2.1 - l1.1).powi(2)
+}
+
+// returns the k nearest alive nodes of a location accepted by the filter, by sorting every node
+fn brute_force<F: Fn(usize) -> bool>(locations: &[(f64, f64)], removed: &[bool], location: (f64, f64), k: usize, filter: F) -> Vec<usize> {
+  let mut nodes: Vec<usize> = (0..locations.len()).filter(|&node| !removed[node] && filter(node)).collect();
+  nodes.sort_by(|&n1, &n2| squared_distance(location, locations[n1]).partial_cmp(&squared_distance(location, locations[n2])).expect("Error while sorting nodes"));
+  nodes.truncate(k);
+  nodes
+}
+
+#[test]
+fn k_nearest_matches_brute_force() {
+  let mut rng = StdRng::seed_from_u64(42);
+  let locations: Vec<(f64, f64)> = (0..1000).map(|_| (rng.gen_range(-500.0..500.0), rng.gen_range(-500.0..500.0))).collect();
+  let tree = KdTree::new(&locations);
+  let removed = vec![false; locations.len()];
+
+  for _ in 0..200 {
+    let location = (rng.gen_range(-600.0..600.0), rng.gen_range(-600.0..600.0));
+    for k in [0, 1, 5, 20] {
+      assert_eq!(tree.k_nearest(location, k, |_| true), brute_force(&locations, &removed, location, k, |_| true));
+    }
+  }
+
+  // the query location itself can be excluded by the filter
+  for node in 0..locations.len() {
+    assert_eq!(tree.k_nearest(locations[node], 3, |neighbor| neighbor != node), brute_force(&locations, &removed, locations[node], 3, |neighbor| neighbor != node));
+  }
+}
+
+#[test]
+fn removed_nodes_are_never_returned() {
+  let mut rng = StdRng::seed_from_u64(7);
+  let locations: Vec<(f64, f64)> = (0..500).map(|_| (rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0))).collect();
+  let mut tree = KdTree::new(&locations);
+  let mut removed = vec![false; locations.len()];
+
+  // remove the nodes in a random order, some of them twice, querying the tree after each removal
+  let mut order: Vec<usize> = (0..locations.len()).collect();
+  order.shuffle(&mut rng);
+  for (index, &node) in order.iter().enumerate() {
+    tree.remove(node);
+    removed[node] = true;
+    if index % 3 == 0 {
+      tree.remove(node);
+    }
+
+    let location = (rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+    assert_eq!(tree.k_nearest(location, 10, |_| true), brute_force(&locations, &removed, location, 10, |_| true));
+    assert_eq!(tree.nearest(location, |_| true), brute_force(&locations, &removed, location, 1, |_| true).first().copied());
+  }
+
+  // every node has been removed
+  assert_eq!(tree.nearest((50.0, 50.0), |_| true), None);
+}
+
+#[test]
+fn duplicate_locations_are_all_found() {
+  let locations = vec![(1.0, 1.0); 8];
+  let mut tree = KdTree::new(&locations);
+  let mut nearest = tree.k_nearest((1.0, 1.0), 8, |_| true);
+  nearest.sort_unstable();
+  assert_eq!(nearest, (0..8).collect::<Vec<usize>>());
+
+  tree.remove(3);
+  assert_eq!(tree.k_nearest((0.0, 0.0), 8, |_| true).len(), 7);
+  assert!(!tree.k_nearest((0.0, 0.0), 8, |_| true).contains(&3));
+}
@@ -0,0 +1,140 @@
+use rand::prelude::*;
+use tsp_solver::dataset::{Dataset,DatasetOptions,Matrix};
+use tsp_solver::storage::{CacheMode,FlatMatrix,Layout,Precision,Rounding};
+
+// returns a random symmetric matrix of integer distances, with a zero diagonal
+fn symmetric_rows(size: usize, rng: &mut StdRng) -> Vec<Vec<f64>> {
+  let draws: Vec<Vec<f64>> = (0..size).map(|_| (0..size).map(|_| rng.gen_range(1..100_000) as f64).collect()).collect();
+  (0..size)
+    .map(|i| (0..size).map(|j| if i == j { 0.0 } else { draws[i.min(j)][i.max(j)] }).collect())
+    .collect()
+}
+
+#[test]
+fn full_and_triangular_layouts_store_the_same_values() {
+  let mut rng = StdRng::seed_from_u64(42);
+  for size in 1..=40 {
+    let rows = symmetric_rows(size, &mut rng);
+    let full = FlatMatrix::from_fn(size, false, |i, j| rows[i][j]);
+    let triangular = FlatMatrix::from_fn(size, true, |i, j| rows[i][j]);
+
+    assert_eq!(full.memory_size(), size * size * 8);
+    assert_eq!(triangular.memory_size(), size * (size + 1) / 2 * 8);
+    for (i, row) in rows.iter().enumerate() {
+      for (j, &distance) in row.iter().enumerate() {
+        assert_eq!(full.get(i, j), distance);
+        assert_eq!(triangular.get(i, j), distance);
+      }
+    }
+  }
+}
+
+#[test]
+fn triangular_layout_only_computes_the_upper_triangle() {
+  // every value of the upper triangle is computed once, in row order
+  let size = 7;
+  let matrix = FlatMatrix::from_fn(size, true, |i, j| {
+    assert!(i <= j);
+    (i * size + j) as f64
+  });
+  for i in 0..size {
+    for j in 0..size {
+      assert_eq!(matrix.get(i, j), (i.min(j) * size + i.max(j)) as f64);
+    }
+  }
+}
+
+#[test]
+fn every_precision_and_layout_round_trips_the_distances() {
+  let mut rng = StdRng::seed_from_u64(7);
+  let rows = symmetric_rows(25, &mut rng);
+  for precision in [Precision::F64, Precision::F32, Precision::Integer] {
+    for layout in [Layout::Auto, Layout::Full, Layout::Triangular] {
+      let matrix = Matrix::from_rows(&rows, precision, layout, Rounding::None);
+      // integers below 2^24 are exact in f32
+      for (i, row) in rows.iter().enumerate() {
+        for (j, &distance) in row.iter().enumerate() {
+          assert_eq!(matrix.get(i, j), distance);
+        }
+      }
+      assert!(matrix.is_symmetric());
+    }
+  }
+}
+
+#[test]
+fn compact_precisions_keep_asymmetric_and_forbidden_distances() {
+  let rows = vec![
+    vec![0.0, 1.5, f64::INFINITY],
+    vec![2.25, 0.0, 3.0],
+    vec![4.0, f64::INFINITY, 0.0]
+  ];
+  let f32_matrix = Matrix::from_rows(&rows, Precision::F32, Layout::Auto, Rounding::None);
+  let integer_matrix = Matrix::from_rows(&rows, Precision::Integer, Layout::Full, Rounding::Nint);
+  assert!(!f32_matrix.is_symmetric());
+  for (i, row) in rows.iter().enumerate() {
+    for (j, &distance) in row.iter().enumerate() {
+      assert_eq!(f32_matrix.get(i, j), distance);
+      assert_eq!(integer_matrix.get(i, j), Rounding::Nint.round(distance));
+    }
+  }
+  assert_eq!(integer_matrix.get(1, 0), 2.0);
+  assert_eq!(integer_matrix.get(0, 2), f64::INFINITY);
+}
+
+#[test]
+fn distances_are_rounded_following_the_tsplib_rules() {
+  assert_eq!(Rounding::None.round(2.5), 2.5);
+  assert_eq!(Rounding::Nint.round(2.5), 3.0);
+  assert_eq!(Rounding::Nint.round(2.49), 2.0);
+  assert_eq!(Rounding::Ceil.round(2.01), 3.0);
+  assert_eq!(Rounding::Ceil.round(2.0), 2.0);
+
+  // sqrt(3^2 + 4^2) = 5, sqrt((30^2 + 40^2) / 10) = 15.81 rounds to 16, sqrt(250 / 10) = 5 stays 5
+  assert_eq!(Rounding::Nint.distance((0.0, 0.0), (3.0, 4.0)), 5.0);
+  assert_eq!(Rounding::Att.distance((0.0, 0.0), (30.0, 40.0)), 16.0);
+  assert_eq!(Rounding::Att.distance((0.0, 0.0), (15.0, 5.0)), 5.0);
+  // sqrt(10 / 10) = 1 exactly, while sqrt(11 / 10) = 1.05 is rounded up to 2 as its nearest integer is below it
+  assert_eq!(Rounding::Att.distance((0.0, 0.0), (1.0, 3.0)), 1.0);
+  assert_eq!(Rounding::Att.distance((0.0, 0.0), (1.0, 3.1623)), 2.0);
+}
+
+#[test]
+fn att48_optimal_tour_has_the_tsplib_length() {
+  let options = DatasetOptions { rounding: Rounding::Att, ..DatasetOptions::default() };
+  let dataset = Dataset::from_file(&format!("{}/datasets/att48/att48_locations.json", env!("CARGO_MANIFEST_DIR")), &options);
+  assert!(dataset.distance_matrix.is_integer());
+
+  // optimal tour of the TSPLIB (att48.opt.tour), the cities 1 to 48 being labeled AA to BV
+  let tour = [1, 8, 38, 31, 44, 18, 7, 28, 6, 37, 19, 27, 17, 43, 30, 36, 46, 33, 20, 47, 21, 32, 39, 48, 5, 42, 24, 10, 45, 35, 4, 26, 2, 29, 34, 41, 16, 22, 3, 23, 14, 25, 13, 11, 12, 15, 40, 9];
+  let nodes: Vec<usize> = tour.iter().map(|city| city - 1).collect();
+  let length: f64 = (0..nodes.len()).map(|index| dataset.distance_matrix.get(nodes[index], nodes[(index + 1) % nodes.len()])).sum();
+  assert_eq!(length, 10628.0);
+}
+
+#[test]
+fn every_cache_mode_returns_the_same_distances() {
+  let mut rng = StdRng::seed_from_u64(3);
+  let locations: Vec<(f64, f64)> = (0..200).map(|_| (rng.gen_range(0.0..1000.0), rng.gen_range(0.0..1000.0))).collect();
+  let labels: Vec<String> = (0..locations.len()).map(|node| format!("N{}", node)).collect();
+  for rounding in [Rounding::None, Rounding::Nint, Rounding::Att] {
+    let build = |cache: CacheMode| {
+      let options = DatasetOptions { cache, ..DatasetOptions::default() };
+      Dataset::from_matrix(labels.clone(), Matrix::Locations(locations.clone(), rounding), &options)
+    };
+    let (full, neighbors, computed) = (build(CacheMode::Full), build(CacheMode::Neighbors), build(CacheMode::None));
+    assert!(matches!(full.distance_matrix, Matrix::Distances(_) | Matrix::IntegerDistances(_)));
+    assert!(matches!(neighbors.distance_matrix, Matrix::CachedLocations(_)));
+    assert!(matches!(computed.distance_matrix, Matrix::Locations(_, _)));
+
+    // the neighbors cache stores the distances to the candidates and computes the other ones, both being compared
+    for from in labels.iter() {
+      for to in labels.iter() {
+        let distance = computed.distance_between(from, to).expect("Unable to find the nodes");
+        assert_eq!(distance, rounding.distance(locations[computed.node(from).unwrap()], locations[computed.node(to).unwrap()]));
+        assert_eq!(full.distance_between(from, to), Ok(distance), "full cache distance from {} to {}", from, to);
+        assert_eq!(neighbors.distance_between(from, to), Ok(distance), "neighbors cache distance from {} to {}", from, to);
+      }
+    }
+  }
+}
@@ -0,0 +1,141 @@
+use std::process::Command;
+use tsp_solver::dataset::{Dataset,DatasetOptions};
+use tsp_solver::generation::Generation;
+use tsp_solver::schedule::TimeWindowsMode;
+use tsp_solver::tours::{self,TourEvaluation};
+
+fn path(file_name: &str) -> String {
+  format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file_name)
+}
+
+fn labels(labels: &[&str]) -> Vec<String> {
+  labels.iter().map(|label| label.to_string()).collect()
+}
+
+// the tour of demo_tour.txt
+const DEMO_TOUR: [&str; 7] = ["Par", "Bez", "Nic", "Mar", "Nar", "Lyo", "Tou"];
+
+#[test]
+fn valid_tours_are_measured_edge_by_edge() {
+  let dataset = Dataset::from_file(&path("demo/demo.json"), &DatasetOptions::default());
+  let evaluation = TourEvaluation::new(&dataset, &labels(&DEMO_TOUR));
+  assert!(evaluation.is_valid());
+  let distances: Vec<f64> = evaluation.edges.iter().map(|&(_, _, distance)| distance).collect();
+  assert_eq!(distances, vec![123.0, 76.0, 199.0, 123.0, 134.0, 538.0]);
+  assert!(evaluation.lateness.is_none());
+  // the objective of a plain tour is its length, so it isn't shown
+  let display = evaluation.to_string();
+  assert!(display.contains("length : 1'193\n"));
+  assert!(!display.contains("objective"));
+  assert_eq!(evaluation.individual.expect("The tour should be evaluated").length, 1193.0);
+}
+
+#[test]
+fn broken_rules_are_listed() {
+  let dataset = Dataset::from_file(&path("demo/demo.json"), &DatasetOptions::default());
+  let evaluation = TourEvaluation::new(&dataset, &labels(&["Par", "Bez", "Par", "Ber", "Nic"]));
+  assert!(!evaluation.is_valid());
+  assert_eq!(evaluation.violations, vec![
+    String::from("Par is visited twice"),
+    String::from("the tour visits the unknown node Ber"),
+    String::from("the tour doesn't visit Mar, Lyo, Tou, Nar")
+  ]);
+
+  let evaluation = TourEvaluation::new(&dataset, &labels(&["Ber"]));
+  assert!(evaluation.individual.is_none());
+  assert!(evaluation.violations.contains(&String::from("the tour doesn't visit any node")));
+}
+
+#[test]
+fn tours_without_edges_have_a_zero_length() {
+  // the tour of an orienteering problem can stay at its depot
+  let dataset = Dataset::from_file(&path("demo/demo_prizes.json"), &DatasetOptions::default());
+  let evaluation = TourEvaluation::new(&dataset, &labels(&["Par"]));
+  assert!(evaluation.is_valid());
+  assert!(evaluation.edges.is_empty());
+  assert!(evaluation.to_string().starts_with("length : 0\n"));
+}
+
+#[test]
+fn lateness_is_reported_with_soft_time_windows() {
+  let dataset = Dataset::from_file(&path("demo/demo_time_windows.json"), &DatasetOptions::default());
+  let evaluation = TourEvaluation::new(&dataset, &labels(&DEMO_TOUR));
+  assert!(evaluation.is_valid());
+  assert_eq!(evaluation.lateness, Some((230.0, 1)));
+  let display = evaluation.to_string();
+  assert!(display.contains("late stops : 1 · total lateness : 230.00"));
+  // the length leaves out the lateness penalty of the objective
+  assert!(display.contains("length : 1'193\n"));
+  assert!(display.contains("objective : "));
+
+  // hard time windows forbid late stops
+  let options = DatasetOptions { time_windows_mode: TimeWindowsMode::Hard, ..DatasetOptions::default() };
+  let dataset = Dataset::from_file(&path("demo/demo_time_windows.json"), &options);
+  let evaluation = TourEvaluation::new(&dataset, &labels(&DEMO_TOUR));
+  assert_eq!(evaluation.violations, vec![String::from("1 stops are reached after the end of their time window")]);
+}
+
+// run the evaluate subcommand on a tour file, returning its exit status and its output
+fn evaluate(dataset_file_name: &str, tour: &[&str]) -> (Option<i32>, String) {
+  let tour_file_name = std::env::temp_dir().join(format!("tsp_solver_tour_{}_{}.txt", std::process::id(), tour.join("_")));
+  std::fs::write(&tour_file_name, tour.join("\n")).expect("Unable to write the tour file");
+  let output = Command::new(env!("CARGO_BIN_EXE_tsp_solver"))
+    .args(["-d", &path(dataset_file_name), "evaluate"])
+    .arg(&tour_file_name)
+    .output()
+    .expect("Unable to run the solver");
+  std::fs::remove_file(&tour_file_name).expect("Unable to remove the tour file");
+  (output.status.code(), String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[test]
+fn evaluate_exits_with_an_error_on_invalid_tours() {
+  let (status, output) = evaluate("demo/demo.json", &DEMO_TOUR);
+  assert_eq!(status, Some(0));
+  assert!(output.contains("length : 1'193"));
+  assert!(output.contains("the tour is valid"));
+
+  let (status, output) = evaluate("demo/demo.json", &["Par", "Bez", "Nic"]);
+  assert_eq!(status, Some(1));
+  assert!(output.contains("violations : 1"));
+}
+
+#[test]
+fn initial_tour_is_in_the_first_generation() {
+  let dataset = Dataset::from_file(&path("demo/demo.json"), &DatasetOptions::default());
+  let tour = tours::tour_nodes(&dataset, &tours::read_tour_file(&path("demo/demo_tour.txt"))).expect("The demo tour should be valid");
+  assert_eq!(tour.0, dataset.nodes_of(&labels(&DEMO_TOUR)).expect("Unable to find the nodes of the tour"));
+  assert_eq!(tour.1, 7);
+
+  let generation = Generation::from_tours(1, 10, 20, &dataset, vec![tour.clone()], &mut rand::thread_rng());
+  assert_eq!(generation.population.len(), 20);
+  assert!(generation.population.iter().any(|individual| individual.nodes == tour.0));
+  // the population is sorted by length, so the best individual is at least as short as the tour
+  assert!(generation.population[0].length <= 1193.0);
+}
+
+#[test]
+fn invalid_initial_tours_are_explained() {
+  let dataset = Dataset::from_file(&path("demo/demo.json"), &DatasetOptions::default());
+  assert_eq!(tours::tour_nodes(&dataset, &labels(&["Par", "Ber"])), Err(String::from("the tour visits the unknown node Ber")));
+  assert_eq!(tours::tour_nodes(&dataset, &labels(&["Par", "Bez", "Par"])), Err(String::from("Par is visited twice")));
+  assert_eq!(tours::tour_nodes(&dataset, &labels(&["Par", "Bez", "Nic"])), Err(String::from("the tour visits 3 nodes instead of 7")));
+  assert_eq!(tours::tour_nodes(&dataset, &[]), Err(String::from("the tour doesn't visit any node")));
+}
+
+#[test]
+fn search_exits_with_an_error_on_an_invalid_initial_tour() {
+  let tour_file_name = std::env::temp_dir().join(format!("tsp_solver_initial_tour_{}.txt", std::process::id()));
+  std::fs::write(&tour_file_name, "Par\nBer\n").expect("Unable to write the tour file");
+  let output = Command::new(env!("CARGO_BIN_EXE_tsp_solver"))
+    .args(["-d", &path("demo/demo.json"), "-N", "-g", "1", "-p", "5", "-t"])
+    .arg(&tour_file_name)
+    .output()
+    .expect("Unable to run the solver");
+  std::fs::remove_file(&tour_file_name).expect("Unable to remove the tour file");
+
+  let errors = String::from_utf8_lossy(&output.stderr);
+  assert_eq!(output.status.code(), Some(1));
+  assert!(errors.contains("The initial tour isn't valid : the tour visits the unknown node Ber"));
+  assert!(!errors.contains("panicked"));
+}